@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 use gpui::{
     deferred, div, prelude::FluentBuilder as _, px, AnyView, App, AppContext as _, Axis, Context,
@@ -12,7 +12,11 @@ use ui::{prelude::Window, theme::ActiveTheme, IconName, StyledExt as _};
 
 const RESIZE_HANDLE_SIZE: Pixels = Pixels(6.);
 
-use crate::{DraggedDock, Event};
+use crate::{
+    pane::Pane,
+    persistence::model::{DockData, SerializedPanel},
+    DraggedDock, Event,
+};
 
 use super::workspace::Workspace;
 
@@ -73,6 +77,26 @@ pub trait Panel: Focusable + EventEmitter<PanelEvent> + Render + Sized {
     fn starts_open(&self, _window: &Window, cx: &App) -> bool {
         true
     }
+    /// Return the internal state of the panel to save with the workspace layout.
+    fn serialize_state(&self, window: &Window, cx: &App) -> Option<serde_json::Value> {
+        None
+    }
+    /// Restore the internal state returned by [`Panel::serialize_state`].
+    ///
+    /// Called when the panel is added to a dock that has a saved state for it.
+    fn restore_state(
+        &mut self,
+        state: serde_json::Value,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+    }
+    /// Return the pane the panel shows its items in, if any, to include its
+    /// navigation history in the workspace's. The pane must be created with
+    /// [`Workspace::create_pane_for_panel`].
+    fn pane(&self) -> Option<Entity<Pane>> {
+        None
+    }
 }
 
 pub trait PanelHandle: Send + Sync {
@@ -88,6 +112,9 @@ pub trait PanelHandle: Send + Sync {
     fn set_active(&self, active: bool, window: &mut Window, cx: &mut App);
     fn is_zoomed(&self, window: &Window, cx: &App) -> bool;
     fn set_zoomed(&self, zoomed: bool, window: &mut Window, cx: &mut App);
+    fn serialize_state(&self, window: &Window, cx: &App) -> Option<serde_json::Value>;
+    fn restore_state(&self, state: serde_json::Value, window: &mut Window, cx: &mut App);
+    fn pane(&self, cx: &App) -> Option<Entity<Pane>>;
     fn to_any(&self) -> AnyView;
 }
 
@@ -143,6 +170,18 @@ where
         self.update(cx, |this, cx| this.set_zoomed(zoomed, window, cx));
     }
 
+    fn serialize_state(&self, window: &Window, cx: &App) -> Option<serde_json::Value> {
+        self.read(cx).serialize_state(window, cx)
+    }
+
+    fn restore_state(&self, state: serde_json::Value, window: &mut Window, cx: &mut App) {
+        self.update(cx, |this, cx| this.restore_state(state, window, cx));
+    }
+
+    fn pane(&self, cx: &App) -> Option<Entity<Pane>> {
+        self.read(cx).pane()
+    }
+
     fn to_any(&self) -> AnyView {
         self.clone().into()
     }
@@ -165,6 +204,9 @@ pub struct Dock {
     active_panel_index: usize,
     focus_handle: FocusHandle,
     resizeable: bool,
+    /// The last known state of the panels, keyed by their persistent name,
+    /// restored when a panel with the same name is added.
+    panel_states: BTreeMap<String, SerializedPanel>,
     _subscriptions: [Subscription; 1],
 }
 
@@ -197,6 +239,7 @@ impl Dock {
                 is_open: false,
                 focus_handle: focus_handle.clone(),
                 resizeable: true,
+                panel_states: BTreeMap::new(),
                 _subscriptions: [focus_subscription],
             }
         });
@@ -260,6 +303,7 @@ impl Dock {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        let weak_workspace = workspace.clone();
         let subscriptions = [
             cx.observe(&panel, |_, _, cx| cx.notify()),
             cx.subscribe_in(
@@ -316,15 +360,44 @@ impl Dock {
             ),
         ];
 
-        let _name = panel.persistent_name().to_string();
+        let name = panel.persistent_name().to_string();
 
         self.panel_entries.push(PanelEntry {
             panel: Arc::new(panel.clone()),
             _subscriptions: subscriptions,
         });
+        let panel_ix = self.panel_entries.len() - 1;
+
+        if let Some(state) = self.panel_states.get(&name).cloned() {
+            if let Some(size) = state.size {
+                panel.set_size(Some(px(size)), window, cx);
+            }
+            if let Some(panel_state) = state.state {
+                panel.restore_state(panel_state, window, cx);
+            }
 
-        if panel.read(cx).starts_open(window, cx) {
-            self.activate_panel(self.panel_entries.len() - 1, window, cx);
+            if state.active {
+                self.activate_panel(panel_ix, window, cx);
+                if state.open {
+                    self.set_open(true, window, cx);
+                    if state.zoom {
+                        self.set_panel_zoomed(&panel.to_any(), true, window, cx);
+                        let position = self.position;
+                        let panel = panel.downgrade();
+                        cx.defer_in(window, move |_, _, cx| {
+                            weak_workspace
+                                .update(cx, |workspace, cx| {
+                                    workspace.zoomed = Some(panel.into());
+                                    workspace.zoomed_position = Some(position);
+                                    cx.emit(Event::ZoomChanged);
+                                })
+                                .ok();
+                        });
+                    }
+                }
+            }
+        } else if panel.read(cx).starts_open(window, cx) {
+            self.activate_panel(panel_ix, window, cx);
             self.set_open(true, window, cx);
         }
 
@@ -343,6 +416,13 @@ impl Dock {
             .iter()
             .position(|entry| entry.panel.id() == Entity::entity_id(panel))
         {
+            let name = self.panel_entries[panel_ix]
+                .panel
+                .persistent_name()
+                .to_string();
+            let state = self.serialize_panel(panel_ix, window, cx);
+            self.panel_states.insert(name, state);
+
             if panel_ix == self.active_panel_index {
                 self.active_panel_index = 0;
                 self.set_open(false, window, cx);
@@ -354,10 +434,20 @@ impl Dock {
         }
     }
 
+    pub fn panels(&self) -> impl Iterator<Item = &Arc<dyn PanelHandle>> {
+        self.panel_entries.iter().map(|entry| &entry.panel)
+    }
+
     pub fn panels_len(&self) -> usize {
         self.panel_entries.len()
     }
 
+    pub fn panel_index_for_persistent_name(&self, name: &str) -> Option<usize> {
+        self.panel_entries
+            .iter()
+            .position(|entry| entry.panel.persistent_name() == name)
+    }
+
     pub fn activate_panel(&mut self, panel_ix: usize, window: &mut Window, cx: &mut Context<Self>) {
         if panel_ix != self.active_panel_index {
             if let Some(active_panel) = self.panel_entries.get(self.active_panel_index) {
@@ -423,6 +513,74 @@ impl Dock {
             cx.notify();
         }
     }
+
+    fn serialize_panel(&self, panel_ix: usize, window: &Window, cx: &App) -> SerializedPanel {
+        let panel = &self.panel_entries[panel_ix].panel;
+        let active = panel_ix == self.active_panel_index;
+        SerializedPanel {
+            size: Some(panel.size(window, cx).0),
+            zoom: panel.is_zoomed(window, cx),
+            open: active && self.is_open,
+            active,
+            state: panel.serialize_state(window, cx),
+        }
+    }
+
+    pub(crate) fn serialize(&self, window: &Window, cx: &App) -> DockData {
+        let active_panel = self.active_panel();
+
+        // Keep the state of panels that are not added yet, so it survives until they are.
+        let mut panels = self.panel_states.clone();
+        for (panel_ix, entry) in self.panel_entries.iter().enumerate() {
+            panels.insert(
+                entry.panel.persistent_name().to_string(),
+                self.serialize_panel(panel_ix, window, cx),
+            );
+        }
+
+        DockData {
+            visible: self.is_open,
+            active_panel: active_panel.map(|panel| panel.persistent_name().to_string()),
+            zoom: active_panel.map_or(false, |panel| panel.is_zoomed(window, cx)),
+            panels,
+        }
+    }
+
+    /// Restore the dock from serialized data, resizing the panels that have already
+    /// been added and activating the one that was active.
+    ///
+    /// The state of the panels is remembered, so that panels added later are
+    /// restored as well.
+    pub(crate) fn load(&mut self, data: &DockData, window: &mut Window, cx: &mut Context<Self>) {
+        self.panel_states = data.panels.clone();
+        for entry in &self.panel_entries {
+            let Some(state) = data.panels.get(entry.panel.persistent_name()) else {
+                continue;
+            };
+
+            if let Some(size) = state.size {
+                entry.panel.set_size(Some(px(size)), window, cx);
+            }
+            if let Some(panel_state) = state.state.clone() {
+                entry.panel.restore_state(panel_state, window, cx);
+            }
+        }
+
+        if let Some(ix) = data
+            .active_panel
+            .as_ref()
+            .and_then(|name| self.panel_index_for_persistent_name(name))
+        {
+            self.activate_panel(ix, window, cx);
+            if data.zoom {
+                if let Some(panel) = self.active_panel() {
+                    panel.set_zoomed(true, window, cx);
+                }
+            }
+        }
+
+        self.set_open(data.visible, window, cx);
+    }
 }
 
 impl Render for Dock {
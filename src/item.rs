@@ -1,13 +1,19 @@
-use std::any::TypeId;
+use std::{
+    any::{Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
 
+use anyhow::{anyhow, Result};
 use gpui::{
     AnyElement, AnyView, App, Context, Element as _, Entity, EntityId, EventEmitter, FocusHandle,
-    Focusable, Pixels, Point, Render, SharedString, WeakEntity,
+    Focusable, Global, Pixels, Point, Render, SharedString, Styled as _, Task, WeakEntity,
 };
-use ui::prelude::Window;
+use ui::{popup_menu::PopupMenu, prelude::Window, theme::ActiveTheme as _, Icon};
 
 use super::{
-    pane::{self, Pane},
+    pane::{self, ItemNavHistory, Pane},
+    persistence::model::ItemId,
     workspace::{Workspace, WorkspaceId},
 };
 
@@ -22,6 +28,19 @@ pub enum ItemEvent {
 pub struct TabContentParams {
     pub detail: Option<usize>,
     pub selected: bool,
+    /// Whether the item is pinned, pinned tabs are rendered compactly.
+    pub is_pinned: bool,
+    /// Whether the item is the preview item of its pane, usually rendered in italic.
+    pub preview: bool,
+}
+
+/// The state of an item, shown by the color of its tab icon.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TabIconState {
+    #[default]
+    Normal,
+    Warning,
+    Error,
 }
 
 pub trait Item: Focusable + EventEmitter<Self::Event> + Render + Sized {
@@ -32,6 +51,23 @@ pub trait Item: Focusable + EventEmitter<Self::Event> + Render + Sized {
         gpui::Empty.into_any()
     }
 
+    /// Returns the icon shown before the content of the tab.
+    fn tab_icon(&self, _window: &Window, _cx: &App) -> Option<Icon> {
+        None
+    }
+
+    /// Returns the state of the item, shown by the color of its tab icon.
+    fn tab_icon_state(&self, _cx: &App) -> TabIconState {
+        TabIconState::Normal
+    }
+
+    /// Returns the title of the tab as plain text, copied by [`CopyTabTitle`].
+    ///
+    /// [`CopyTabTitle`]: crate::pane::CopyTabTitle
+    fn tab_title(&self, _: &App) -> Option<SharedString> {
+        None
+    }
+
     /// Returns the tooltip for the tab.
     fn tab_tooltip(&self, _: &App) -> Option<SharedString> {
         None
@@ -42,6 +78,43 @@ pub trait Item: Focusable + EventEmitter<Self::Event> + Render + Sized {
         None
     }
 
+    /// Returns whether the item has unsaved changes.
+    ///
+    /// Closing a dirty item asks the user to save it first, and dirty items are never closed
+    /// automatically.
+    fn is_dirty(&self, _: &App) -> bool {
+        false
+    }
+
+    /// Returns whether the saved data of the item was changed elsewhere since it was edited.
+    fn has_conflict(&self, _: &App) -> bool {
+        false
+    }
+
+    /// Returns whether the item can be saved with [`Item::save`].
+    fn can_save(&self, _: &App) -> bool {
+        false
+    }
+
+    /// Invoked before the item is closed, resolve to `false` to keep it open.
+    ///
+    /// Refusing to close also stops closing the other items of a bulk close.
+    fn can_close(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> Task<bool> {
+        Task::ready(true)
+    }
+
+    /// Save the changes of the item.
+    fn save(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> Task<Result<()>> {
+        Task::ready(Err(anyhow!(
+            "save() must be implemented if can_save() returns true"
+        )))
+    }
+
+    /// Append the item specific entries to the context menu of the tab.
+    fn tab_context_menu(&self, menu: PopupMenu, _window: &Window, _cx: &App) -> PopupMenu {
+        menu
+    }
+
     fn to_item_events(_event: &Self::Event, _f: impl FnMut(ItemEvent)) {}
 
     /// Invoked when the item is deactivated.
@@ -89,6 +162,59 @@ pub trait Item: Focusable + EventEmitter<Self::Event> + Render + Sized {
     fn pixel_position_of_cursor(&self, _: &App) -> Option<Point<Pixels>> {
         None
     }
+
+    /// Invoked when the item is added to a pane, with the navigation history of the pane
+    /// to record positions in the item.
+    fn set_nav_history(&mut self, _: ItemNavHistory, _window: &mut Window, _: &mut Context<Self>) {}
+
+    /// Move to a position recorded with [`ItemNavHistory::push`].
+    ///
+    /// Returns `false` if there was nothing to move to, to skip to the next entry.
+    fn navigate(&mut self, _: Box<dyn Any>, _window: &mut Window, _: &mut Context<Self>) -> bool {
+        false
+    }
+}
+
+/// An item that can be saved with the workspace layout and recreated when it is restored.
+///
+/// Serializable items must be registered with [`register_serializable_item`].
+pub trait SerializableItem: Item {
+    /// A unique name for this kind of item, used to find the item type on restore.
+    fn serialized_item_kind() -> &'static str;
+
+    /// Returns the state needed to recreate this item, if any.
+    fn serialize(&self, _window: &Window, _cx: &App) -> Option<serde_json::Value> {
+        None
+    }
+
+    /// Recreate the item from the state returned by [`SerializableItem::serialize`].
+    fn deserialize(
+        workspace: WeakEntity<Workspace>,
+        pane: WeakEntity<Pane>,
+        workspace_id: Option<WorkspaceId>,
+        item_id: ItemId,
+        state: Option<serde_json::Value>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Entity<Self>>>;
+}
+
+pub trait SerializableItemHandle: ItemHandle {
+    fn serialized_item_kind(&self) -> &'static str;
+    fn serialize(&self, window: &Window, cx: &App) -> Option<serde_json::Value>;
+}
+
+impl<T> SerializableItemHandle for Entity<T>
+where
+    T: SerializableItem,
+{
+    fn serialized_item_kind(&self) -> &'static str {
+        T::serialized_item_kind()
+    }
+
+    fn serialize(&self, window: &Window, cx: &App) -> Option<serde_json::Value> {
+        self.read(cx).serialize(window, cx)
+    }
 }
 
 pub trait ItemHandle: 'static + Send {
@@ -102,8 +228,17 @@ pub trait ItemHandle: 'static + Send {
         handler: Box<dyn Fn(ItemEvent, &mut Window, &mut App)>,
     ) -> gpui::Subscription;
     fn item_focus_handle(&self, window: &Window, cx: &App) -> FocusHandle;
+    fn tab_icon(&self, window: &Window, cx: &App) -> Option<Icon>;
+    fn tab_icon_state(&self, cx: &App) -> TabIconState;
+    fn tab_title(&self, cx: &App) -> Option<SharedString>;
     fn tab_tooltip(&self, cx: &App) -> Option<SharedString>;
     fn tab_description(&self, detail: usize, cx: &App) -> Option<SharedString>;
+    fn is_dirty(&self, cx: &App) -> bool;
+    fn has_conflict(&self, cx: &App) -> bool;
+    fn can_save(&self, cx: &App) -> bool;
+    fn can_close(&self, window: &mut Window, cx: &mut App) -> Task<bool>;
+    fn save(&self, window: &mut Window, cx: &mut App) -> Task<Result<()>>;
+    fn tab_context_menu(&self, menu: PopupMenu, window: &Window, cx: &App) -> PopupMenu;
     fn tab_content(&self, params: TabContentParams, window: &Window, cx: &App) -> AnyElement;
     fn dragged_tab_content(
         &self,
@@ -136,6 +271,9 @@ pub trait ItemHandle: 'static + Send {
     fn downgrade_item(&self) -> Box<dyn WeakItemHandle>;
     fn boxed_clone(&self) -> Box<dyn ItemHandle>;
     fn act_as_type<'a>(&'a self, type_id: TypeId, cx: &'a App) -> Option<AnyView>;
+    fn to_serializable_item_handle(&self, cx: &App) -> Option<Box<dyn SerializableItemHandle>>;
+    fn set_nav_history(&self, history: ItemNavHistory, window: &mut Window, cx: &mut App);
+    fn navigate(&self, data: Box<dyn Any>, window: &mut Window, cx: &mut App) -> bool;
 }
 
 pub trait WeakItemHandle: Send + Sync {
@@ -152,6 +290,16 @@ impl dyn ItemHandle {
         self.act_as_type(TypeId::of::<V>(), cx)
             .and_then(|t| t.downcast().ok())
     }
+
+    /// Returns the tab icon of the item, colored by its [`TabIconState`].
+    pub fn colored_tab_icon(&self, window: &Window, cx: &App) -> Option<Icon> {
+        let icon = self.tab_icon(window, cx)?;
+        Some(match self.tab_icon_state(cx) {
+            TabIconState::Normal => icon,
+            TabIconState::Warning => icon.text_color(cx.theme().warning),
+            TabIconState::Error => icon.text_color(cx.theme().danger),
+        })
+    }
 }
 
 impl<T: Item> ItemHandle for Entity<T> {
@@ -170,6 +318,18 @@ impl<T: Item> ItemHandle for Entity<T> {
         self.read(cx).focus_handle(cx)
     }
 
+    fn tab_icon(&self, window: &Window, cx: &App) -> Option<Icon> {
+        self.read(cx).tab_icon(window, cx)
+    }
+
+    fn tab_icon_state(&self, cx: &App) -> TabIconState {
+        self.read(cx).tab_icon_state(cx)
+    }
+
+    fn tab_title(&self, cx: &App) -> Option<SharedString> {
+        self.read(cx).tab_title(cx)
+    }
+
     fn tab_tooltip(&self, cx: &App) -> Option<SharedString> {
         self.read(cx).tab_tooltip(cx)
     }
@@ -178,6 +338,30 @@ impl<T: Item> ItemHandle for Entity<T> {
         self.read(cx).tab_description(detail, cx)
     }
 
+    fn is_dirty(&self, cx: &App) -> bool {
+        self.read(cx).is_dirty(cx)
+    }
+
+    fn has_conflict(&self, cx: &App) -> bool {
+        self.read(cx).has_conflict(cx)
+    }
+
+    fn can_save(&self, cx: &App) -> bool {
+        self.read(cx).can_save(cx)
+    }
+
+    fn can_close(&self, window: &mut Window, cx: &mut App) -> Task<bool> {
+        self.update(cx, |item, cx| item.can_close(window, cx))
+    }
+
+    fn save(&self, window: &mut Window, cx: &mut App) -> Task<Result<()>> {
+        self.update(cx, |item, cx| item.save(window, cx))
+    }
+
+    fn tab_context_menu(&self, menu: PopupMenu, window: &Window, cx: &App) -> PopupMenu {
+        self.read(cx).tab_context_menu(menu, window, cx)
+    }
+
     fn tab_content(&self, params: TabContentParams, window: &Window, cx: &App) -> AnyElement {
         self.read(cx).tab_content(params, window, cx)
     }
@@ -259,7 +443,8 @@ impl<T: Item> ItemHandle for Entity<T> {
                             });
                         }
 
-                        _ => {}
+                        // The pane the item is in makes it permanent.
+                        ItemEvent::Edit => {}
                     });
                 },
             ));
@@ -272,9 +457,9 @@ impl<T: Item> ItemHandle for Entity<T> {
             .detach();
         }
 
-        // cx.defer(|workspace, cx| {
-        //     workspace.serialize_workspace(cx);
-        // });
+        cx.defer_in(window, |workspace, window, cx| {
+            workspace.serialize_workspace(window, cx);
+        });
     }
 
     fn deactivated(&self, window: &mut Window, cx: &mut App) {
@@ -308,6 +493,18 @@ impl<T: Item> ItemHandle for Entity<T> {
     fn downgrade_item(&self) -> Box<dyn WeakItemHandle> {
         Box::new(self.downgrade())
     }
+
+    fn to_serializable_item_handle(&self, cx: &App) -> Option<Box<dyn SerializableItemHandle>> {
+        SerializableItemRegistry::view_to_serializable_item_handle(self.to_any(), cx)
+    }
+
+    fn set_nav_history(&self, history: ItemNavHistory, window: &mut Window, cx: &mut App) {
+        self.update(cx, |this, cx| this.set_nav_history(history, window, cx));
+    }
+
+    fn navigate(&self, data: Box<dyn Any>, window: &mut Window, cx: &mut App) -> bool {
+        self.update(cx, |this, cx| this.navigate(data, window, cx))
+    }
 }
 
 impl From<Box<dyn ItemHandle>> for AnyView {
@@ -337,3 +534,110 @@ impl<T: Item> WeakItemHandle for WeakEntity<T> {
         self.upgrade().map(|v| Box::new(v) as Box<dyn ItemHandle>)
     }
 }
+
+type DeserializeItem = fn(
+    WeakEntity<Workspace>,
+    WeakEntity<Pane>,
+    Option<WorkspaceId>,
+    ItemId,
+    Option<serde_json::Value>,
+    &mut Window,
+    &mut App,
+) -> Task<Result<Box<dyn ItemHandle>>>;
+
+#[derive(Clone)]
+struct SerializableItemDescriptor {
+    deserialize: DeserializeItem,
+    view_to_serializable_item: fn(AnyView) -> Box<dyn SerializableItemHandle>,
+}
+
+/// The registry of [`SerializableItem`] types, keyed by their kind.
+#[derive(Default)]
+struct SerializableItemRegistry {
+    descriptors_by_kind: HashMap<Arc<str>, SerializableItemDescriptor>,
+    descriptors_by_type: HashMap<TypeId, SerializableItemDescriptor>,
+}
+
+impl Global for SerializableItemRegistry {}
+
+impl SerializableItemRegistry {
+    #[allow(clippy::too_many_arguments)]
+    fn deserialize(
+        item_kind: &str,
+        workspace: WeakEntity<Workspace>,
+        pane: WeakEntity<Pane>,
+        workspace_id: Option<WorkspaceId>,
+        item_id: ItemId,
+        state: Option<serde_json::Value>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Task<Result<Box<dyn ItemHandle>>> {
+        let Some(descriptor) = Self::descriptor(item_kind, cx) else {
+            return Task::ready(Err(anyhow!(
+                "cannot deserialize {}, descriptor not found",
+                item_kind
+            )));
+        };
+
+        (descriptor.deserialize)(workspace, pane, workspace_id, item_id, state, window, cx)
+    }
+
+    fn view_to_serializable_item_handle(
+        view: AnyView,
+        cx: &App,
+    ) -> Option<Box<dyn SerializableItemHandle>> {
+        let this = cx.try_global::<Self>()?;
+        let descriptor = this.descriptors_by_type.get(&view.entity_type())?;
+        Some((descriptor.view_to_serializable_item)(view))
+    }
+
+    fn descriptor(item_kind: &str, cx: &App) -> Option<SerializableItemDescriptor> {
+        let this = cx.try_global::<Self>()?;
+        this.descriptors_by_kind.get(item_kind).cloned()
+    }
+}
+
+/// Register a [`SerializableItem`] type so that it can be restored with the workspace layout.
+pub fn register_serializable_item<I: SerializableItem>(cx: &mut App) {
+    let serialized_item_kind = I::serialized_item_kind();
+
+    let registry = cx.default_global::<SerializableItemRegistry>();
+    let descriptor = SerializableItemDescriptor {
+        deserialize: |workspace, pane, workspace_id, item_id, state, window, cx| {
+            let task = I::deserialize(workspace, pane, workspace_id, item_id, state, window, cx);
+            cx.foreground_executor()
+                .spawn(async { Ok(Box::new(task.await?) as Box<_>) })
+        },
+        view_to_serializable_item: |view| Box::new(view.downcast::<I>().unwrap()),
+    };
+    registry
+        .descriptors_by_kind
+        .insert(Arc::from(serialized_item_kind), descriptor.clone());
+    registry
+        .descriptors_by_type
+        .insert(TypeId::of::<I>(), descriptor);
+}
+
+/// Recreate an item of a registered [`SerializableItem`] kind.
+#[allow(clippy::too_many_arguments)]
+pub fn deserialize_item(
+    item_kind: &str,
+    workspace: WeakEntity<Workspace>,
+    pane: WeakEntity<Pane>,
+    workspace_id: Option<WorkspaceId>,
+    item_id: ItemId,
+    state: Option<serde_json::Value>,
+    window: &mut Window,
+    cx: &mut App,
+) -> Task<Result<Box<dyn ItemHandle>>> {
+    SerializableItemRegistry::deserialize(
+        item_kind,
+        workspace,
+        pane,
+        workspace_id,
+        item_id,
+        state,
+        window,
+        cx,
+    )
+}
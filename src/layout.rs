@@ -0,0 +1,340 @@
+use std::{collections::HashSet, fmt};
+
+use gpui::{Axis, Context, Entity, EntityId, Window};
+
+use crate::{
+    item::ItemHandle,
+    pane::Pane,
+    pane_group::{normalize_flexes, Member, PaneAxis},
+    Workspace,
+};
+
+/// A declarative description of the center pane tree, applied with [`Workspace::apply_layout`].
+///
+/// ```ignore
+/// let spec = SplitSpec::horizontal([
+///     LayoutSpec::from(PaneSpec::new().item(editor)),
+///     SplitSpec::vertical([PaneSpec::new().item(preview), PaneSpec::new().item(terminal)])
+///         .flexes([2., 1.])
+///         .into(),
+/// ])
+/// .flexes([3., 2.]);
+/// ```
+pub enum LayoutSpec {
+    Split(SplitSpec),
+    Pane(PaneSpec),
+}
+
+impl LayoutSpec {
+    /// Check that the layout can be applied, without creating anything.
+    pub fn validate(&self) -> Result<(), LayoutError> {
+        let mut item_ids = HashSet::new();
+        let mut has_active_pane = false;
+        self.validate_internal(&mut item_ids, &mut has_active_pane)
+    }
+
+    fn validate_internal(
+        &self,
+        item_ids: &mut HashSet<EntityId>,
+        has_active_pane: &mut bool,
+    ) -> Result<(), LayoutError> {
+        match self {
+            Self::Split(SplitSpec {
+                children, flexes, ..
+            }) => {
+                if children.is_empty() {
+                    return Err(LayoutError::EmptySplit);
+                }
+
+                if let Some(flexes) = flexes {
+                    if flexes.len() != children.len() {
+                        return Err(LayoutError::FlexesMismatch {
+                            children: children.len(),
+                            flexes: flexes.len(),
+                        });
+                    }
+                    if let Some(flex) = flexes.iter().find(|flex| !flex.is_finite() || **flex <= 0.)
+                    {
+                        return Err(LayoutError::InvalidFlex(*flex));
+                    }
+                }
+
+                for child in children {
+                    child.validate_internal(item_ids, has_active_pane)?;
+                }
+            }
+            Self::Pane(pane) => {
+                if let Some(index) = pane.active_item {
+                    if index >= pane.items.len() {
+                        return Err(LayoutError::ActiveItemOutOfRange {
+                            index,
+                            len: pane.items.len(),
+                        });
+                    }
+                }
+
+                if pane.active {
+                    if *has_active_pane {
+                        return Err(LayoutError::MultipleActivePanes);
+                    }
+                    *has_active_pane = true;
+                }
+
+                for item in &pane.items {
+                    if !item_ids.insert(item.item_id()) {
+                        return Err(LayoutError::DuplicateItem(item.item_id()));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the ids of the items of every pane, in depth-first order.
+    pub(crate) fn item_ids(&self) -> Vec<EntityId> {
+        match self {
+            Self::Split(split) => split.children.iter().flat_map(Self::item_ids).collect(),
+            Self::Pane(pane) => pane.items.iter().map(|item| item.item_id()).collect(),
+        }
+    }
+
+    /// Create the panes of a validated layout, returns the root member and the pane to activate.
+    pub(crate) fn build(
+        self,
+        panes: &mut Vec<Entity<Pane>>,
+        workspace: &mut Workspace,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) -> (Member, Option<Entity<Pane>>) {
+        match self {
+            Self::Split(SplitSpec {
+                axis,
+                children,
+                flexes,
+            }) => {
+                let mut active_pane = None;
+                let mut members = Vec::new();
+                for child in children {
+                    let (member, child_active_pane) = child.build(panes, workspace, window, cx);
+                    members.push(member);
+                    active_pane = active_pane.or(child_active_pane);
+                }
+
+                if members.len() == 1 {
+                    return (members.remove(0), active_pane);
+                }
+
+                let flexes = flexes.and_then(|flexes| normalize_flexes(&flexes));
+                (
+                    Member::Axis(PaneAxis::load(axis, members, flexes)),
+                    active_pane,
+                )
+            }
+            Self::Pane(spec) => {
+                let pane = workspace.add_pane(window, cx);
+                pane.update(cx, |pane, cx| {
+                    for item in spec.items {
+                        let index = pane.items_len();
+                        pane.add_item(item, false, false, Some(index), window, cx);
+                    }
+                    if let Some(index) = spec.active_item {
+                        pane.activate_item(index, false, false, window, cx);
+                    }
+                });
+                panes.push(pane.clone());
+                let active_pane = spec.active.then(|| pane.clone());
+                (Member::Pane(pane), active_pane)
+            }
+        }
+    }
+}
+
+impl From<SplitSpec> for LayoutSpec {
+    fn from(split: SplitSpec) -> Self {
+        Self::Split(split)
+    }
+}
+
+impl From<PaneSpec> for LayoutSpec {
+    fn from(pane: PaneSpec) -> Self {
+        Self::Pane(pane)
+    }
+}
+
+/// A split of a [`LayoutSpec`], arranging its children along an axis.
+pub struct SplitSpec {
+    axis: Axis,
+    children: Vec<LayoutSpec>,
+    flexes: Option<Vec<f32>>,
+}
+
+impl SplitSpec {
+    /// Arrange the children side by side, from left to right.
+    pub fn horizontal(children: impl IntoIterator<Item = impl Into<LayoutSpec>>) -> Self {
+        Self::new(Axis::Horizontal, children)
+    }
+
+    /// Arrange the children on top of each other, from top to bottom.
+    pub fn vertical(children: impl IntoIterator<Item = impl Into<LayoutSpec>>) -> Self {
+        Self::new(Axis::Vertical, children)
+    }
+
+    pub fn new(axis: Axis, children: impl IntoIterator<Item = impl Into<LayoutSpec>>) -> Self {
+        Self {
+            axis,
+            children: children.into_iter().map(Into::into).collect(),
+            flexes: None,
+        }
+    }
+
+    /// Set the relative sizes of the children, one per child.
+    ///
+    /// Only the ratios matter, `[2., 1.]` and `[0.66, 0.33]` give the same layout.
+    pub fn flexes(mut self, flexes: impl IntoIterator<Item = f32>) -> Self {
+        self.flexes = Some(flexes.into_iter().collect());
+        self
+    }
+}
+
+/// The items of a pane in a [`LayoutSpec`].
+#[derive(Default)]
+pub struct PaneSpec {
+    items: Vec<Box<dyn ItemHandle>>,
+    active_item: Option<usize>,
+    active: bool,
+}
+
+impl PaneSpec {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an item as the last tab of the pane.
+    pub fn item(mut self, item: impl ItemHandle) -> Self {
+        self.items.push(Box::new(item));
+        self
+    }
+
+    /// Add items as the last tabs of the pane.
+    pub fn items(mut self, items: impl IntoIterator<Item = Box<dyn ItemHandle>>) -> Self {
+        self.items.extend(items);
+        self
+    }
+
+    /// Set the index of the active tab, defaults to the first one.
+    pub fn active_item(mut self, index: usize) -> Self {
+        self.active_item = Some(index);
+        self
+    }
+
+    /// Make this the active pane of the workspace, defaults to the first pane.
+    pub fn activate(mut self) -> Self {
+        self.active = true;
+        self
+    }
+}
+
+/// The reasons a [`LayoutSpec`] can't be applied.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LayoutError {
+    /// A split has no children.
+    EmptySplit,
+    /// The number of flexes of a split doesn't match its number of children.
+    FlexesMismatch { children: usize, flexes: usize },
+    /// A flex is not a positive, finite number.
+    InvalidFlex(f32),
+    /// The active item index of a pane is out of range.
+    ActiveItemOutOfRange { index: usize, len: usize },
+    /// More than one pane is marked as active.
+    MultipleActivePanes,
+    /// The same item is placed more than once.
+    DuplicateItem(EntityId),
+}
+
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptySplit => write!(f, "a split must have at least one child"),
+            Self::FlexesMismatch { children, flexes } => write!(
+                f,
+                "a split with {} children can't have {} flexes",
+                children, flexes
+            ),
+            Self::InvalidFlex(flex) => write!(f, "flex {} is not a positive number", flex),
+            Self::ActiveItemOutOfRange { index, len } => write!(
+                f,
+                "active item index {} is out of range for a pane with {} items",
+                index, len
+            ),
+            Self::MultipleActivePanes => write!(f, "only one pane can be active"),
+            Self::DuplicateItem(item_id) => {
+                write!(f, "item {} is placed more than once", item_id.as_u64())
+            }
+        }
+    }
+}
+
+impl std::error::Error for LayoutError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pane() -> LayoutSpec {
+        PaneSpec::new().into()
+    }
+
+    #[test]
+    fn test_validate() {
+        assert_eq!(pane().validate(), Ok(()));
+        assert_eq!(
+            LayoutSpec::from(
+                SplitSpec::horizontal([pane(), SplitSpec::vertical([pane(), pane()]).into()])
+                    .flexes([3., 2.])
+            )
+            .validate(),
+            Ok(())
+        );
+
+        assert_eq!(
+            LayoutSpec::from(SplitSpec::horizontal(Vec::<LayoutSpec>::new())).validate(),
+            Err(LayoutError::EmptySplit)
+        );
+        assert_eq!(
+            LayoutSpec::from(SplitSpec::horizontal([pane(), pane()]).flexes([1.])).validate(),
+            Err(LayoutError::FlexesMismatch {
+                children: 2,
+                flexes: 1
+            })
+        );
+        assert_eq!(
+            LayoutSpec::from(SplitSpec::vertical([pane(), pane()]).flexes([1., -1.])).validate(),
+            Err(LayoutError::InvalidFlex(-1.))
+        );
+        assert_eq!(
+            LayoutSpec::from(PaneSpec::new().active_item(0)).validate(),
+            Err(LayoutError::ActiveItemOutOfRange { index: 0, len: 0 })
+        );
+        assert_eq!(
+            LayoutSpec::from(SplitSpec::horizontal([
+                PaneSpec::new().activate(),
+                PaneSpec::new().activate(),
+            ]))
+            .validate(),
+            Err(LayoutError::MultipleActivePanes)
+        );
+    }
+
+    #[test]
+    fn test_validate_nested_split() {
+        assert_eq!(
+            LayoutSpec::from(SplitSpec::horizontal([
+                pane(),
+                SplitSpec::vertical(Vec::<LayoutSpec>::new()).into(),
+            ]))
+            .validate(),
+            Err(LayoutError::EmptySplit)
+        );
+    }
+}
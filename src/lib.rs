@@ -1,8 +1,15 @@
+mod tab_overflow_menu;
 mod util;
 mod workspace;
 
 pub mod dock;
 pub mod item;
+pub mod layout;
+pub mod modal_layer;
 pub mod pane;
 pub mod pane_group;
+pub mod persistence;
+pub mod tab_picker;
+pub mod tab_search;
+pub mod tab_switcher;
 pub use workspace::*;
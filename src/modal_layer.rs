@@ -0,0 +1,154 @@
+use gpui::{
+    div, px, AnyView, App, AppContext as _, Context, DismissEvent, Entity, FocusHandle, Focusable,
+    InteractiveElement as _, IntoElement, ManagedView, ParentElement as _, Render, Styled as _,
+    Subscription, Window,
+};
+use ui::{h_flex, v_flex};
+
+/// A view shown on top of the workspace with [`Workspace::toggle_modal`], dismissed by
+/// emitting [`DismissEvent`] or clicking outside of it.
+///
+/// [`Workspace::toggle_modal`]: crate::Workspace::toggle_modal
+pub trait ModalView: ManagedView {
+    /// Invoked before the modal is dismissed, return `false` to keep it open.
+    fn on_before_dismiss(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> bool {
+        true
+    }
+}
+
+trait ModalViewHandle {
+    fn on_before_dismiss(&mut self, window: &mut Window, cx: &mut App) -> bool;
+    fn view(&self) -> AnyView;
+}
+
+impl<V: ModalView> ModalViewHandle for Entity<V> {
+    fn on_before_dismiss(&mut self, window: &mut Window, cx: &mut App) -> bool {
+        self.update(cx, |this, cx| this.on_before_dismiss(window, cx))
+    }
+
+    fn view(&self) -> AnyView {
+        self.clone().into()
+    }
+}
+
+struct ActiveModal {
+    modal: Box<dyn ModalViewHandle>,
+    focus_handle: FocusHandle,
+    previous_focus_handle: Option<FocusHandle>,
+    _subscriptions: [Subscription; 2],
+}
+
+#[derive(Default)]
+pub struct ModalLayer {
+    active_modal: Option<ActiveModal>,
+}
+
+impl ModalLayer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Show the modal built by `build_view`, or hide it if a modal of the same type is shown.
+    pub fn toggle_modal<V, B>(&mut self, window: &mut Window, cx: &mut Context<Self>, build_view: B)
+    where
+        V: ModalView,
+        B: FnOnce(&mut Window, &mut Context<V>) -> V,
+    {
+        if let Some(active_modal) = &self.active_modal {
+            let is_close = active_modal.modal.view().downcast::<V>().is_ok();
+            let did_close = self.hide_modal(window, cx);
+            if is_close || !did_close {
+                return;
+            }
+        }
+
+        let new_modal = cx.new(|cx| build_view(window, cx));
+        self.show_modal(new_modal, window, cx);
+    }
+
+    fn show_modal<V: ModalView>(
+        &mut self,
+        new_modal: Entity<V>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let focus_handle = cx.focus_handle();
+        self.active_modal = Some(ActiveModal {
+            modal: Box::new(new_modal.clone()),
+            _subscriptions: [
+                cx.subscribe_in(
+                    &new_modal,
+                    window,
+                    |this, _, _: &DismissEvent, window, cx| {
+                        this.hide_modal(window, cx);
+                    },
+                ),
+                cx.on_focus_out(&focus_handle, window, |this, _, window, cx| {
+                    this.hide_modal(window, cx);
+                }),
+            ],
+            previous_focus_handle: window.focused(cx),
+            focus_handle,
+        });
+        cx.defer_in(window, move |_, window, cx| {
+            window.focus(&new_modal.focus_handle(cx));
+        });
+        cx.notify();
+    }
+
+    /// Hide the active modal, returns `false` if the modal refused to be dismissed.
+    pub fn hide_modal(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let Some(active_modal) = self.active_modal.as_mut() else {
+            return false;
+        };
+
+        if !active_modal.modal.on_before_dismiss(window, cx) {
+            return false;
+        }
+
+        if let Some(active_modal) = self.active_modal.take() {
+            if let Some(previous_focus) = active_modal.previous_focus_handle {
+                if active_modal.focus_handle.contains_focused(window, cx) {
+                    previous_focus.focus(window);
+                }
+            }
+            cx.notify();
+        }
+        true
+    }
+
+    pub fn active_modal<V: 'static>(&self) -> Option<Entity<V>> {
+        let active_modal = self.active_modal.as_ref()?;
+        active_modal.modal.view().downcast::<V>().ok()
+    }
+
+    pub fn has_active_modal(&self) -> bool {
+        self.active_modal.is_some()
+    }
+}
+
+impl Render for ModalLayer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(active_modal) = &self.active_modal else {
+            return div();
+        };
+
+        div().absolute().size_full().top_0().left_0().child(
+            v_flex()
+                .h(px(0.0))
+                .top_20()
+                .flex()
+                .flex_col()
+                .items_center()
+                .track_focus(&active_modal.focus_handle)
+                .child(
+                    h_flex()
+                        .occlude()
+                        .child(active_modal.modal.view())
+                        .on_mouse_down_out(cx.listener(|this, _, window, cx| {
+                            this.hide_modal(window, cx);
+                        })),
+                ),
+        )
+    }
+}
@@ -1,37 +1,48 @@
 use std::{
     any::Any,
     cmp,
-    collections::{BTreeSet, HashMap},
+    collections::{BTreeSet, HashMap, VecDeque},
     fmt, mem,
+    num::NonZeroUsize,
     ops::ControlFlow,
     rc::Rc,
-    sync::Arc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
 };
 
 use anyhow::Result;
 use gpui::{
-    actions, div, impl_internal_actions, prelude::FluentBuilder as _, px, App, AppContext, Context,
-    DefiniteLength, DragMoveEvent, Element as _, Entity, EntityId, EventEmitter, FocusHandle,
-    FocusOutEvent, Focusable, InteractiveElement as _, IntoElement, KeyContext, ParentElement,
-    Pixels, Point, Render, ScrollHandle, StatefulInteractiveElement, Styled, Subscription, Task,
-    WeakEntity, WeakFocusHandle,
+    actions, div, impl_internal_actions, prelude::FluentBuilder as _, px, AnyElement, App,
+    AppContext, AsyncWindowContext, ClickEvent, ClipboardItem, Context, Corner, DefiniteLength,
+    DragMoveEvent, Element as _, Entity, EntityId, EventEmitter, FocusHandle, FocusOutEvent,
+    Focusable, InteractiveElement as _, IntoElement, KeyContext, MouseButton, NavigationDirection,
+    ParentElement, Pixels, Point, PromptLevel, Render, ScrollHandle, StatefulInteractiveElement,
+    Styled, Subscription, Task, WeakEntity, WeakFocusHandle,
 };
+use parking_lot::Mutex;
 use serde::Deserialize;
 
 use crate::util::ResultExt;
 use ui::{
+    button::{Button, ButtonVariants as _},
+    context_menu::ContextMenuExt as _,
+    h_flex,
     label::Label,
+    popover::Popover,
     prelude::Window,
     tab::{Tab, TabBar},
     theme::ActiveTheme,
     tooltip::Tooltip,
-    v_flex, Colorize as _, Icon, IconName, Selectable, StyledExt,
+    v_flex, Colorize as _, Icon, IconName, Selectable, Sizable as _, StyledExt,
 };
 
 use super::{
-    item::{ItemHandle, TabContentParams},
+    item::{ItemEvent, ItemHandle, TabContentParams, WeakItemHandle},
     pane_group::SplitDirection,
-    workspace::Workspace,
+    tab_overflow_menu,
+    workspace::{MoveItemToNewWindow, Workspace},
 };
 
 #[derive(Clone, Deserialize, PartialEq, Debug)]
@@ -50,6 +61,10 @@ actions!(
         ActivateLastItem,
         CloseItemsToTheLeft,
         CloseItemsToTheRight,
+        PinTab,
+        UnpinTab,
+        TogglePinTab,
+        CopyTabTitle,
         GoBack,
         GoForward,
         ReopenClosedItem,
@@ -61,10 +76,28 @@ actions!(
 );
 
 pub enum Event {
-    AddItem { item: Box<dyn ItemHandle> },
-    ActivateItem { local: bool },
+    AddItem {
+        item: Box<dyn ItemHandle>,
+    },
+    ActivateItem {
+        local: bool,
+    },
     Remove,
-    RemoveItem { item_id: EntityId },
+    RemoveItem {
+        item_id: EntityId,
+    },
+    /// An item was closed by the user, as opposed to moved to another pane.
+    ItemClosed {
+        item: Box<dyn ItemHandle>,
+        index: usize,
+        is_pinned: bool,
+    },
+    ItemPinned {
+        item_id: EntityId,
+    },
+    ItemUnpinned {
+        item_id: EntityId,
+    },
     Split(SplitDirection),
     ChangeItemTitle,
     Focus,
@@ -88,6 +121,24 @@ impl fmt::Debug for Event {
                 .debug_struct("RemoveItem")
                 .field("item_id", item_id)
                 .finish(),
+            Event::ItemClosed {
+                item,
+                index,
+                is_pinned,
+            } => f
+                .debug_struct("ItemClosed")
+                .field("item", &item.item_id())
+                .field("index", index)
+                .field("is_pinned", is_pinned)
+                .finish(),
+            Event::ItemPinned { item_id } => f
+                .debug_struct("ItemPinned")
+                .field("item_id", item_id)
+                .finish(),
+            Event::ItemUnpinned { item_id } => f
+                .debug_struct("ItemUnpinned")
+                .field("item_id", item_id)
+                .finish(),
             Event::Split(direction) => f
                 .debug_struct("Split")
                 .field("direction", direction)
@@ -100,6 +151,206 @@ impl fmt::Debug for Event {
     }
 }
 
+/// The result of closing items, see [`Pane::close_items`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CloseOutcome {
+    /// The number of items that were closed.
+    pub closed: usize,
+    /// Whether closing was cancelled by an item or by the user, leaving the remaining
+    /// items open.
+    pub cancelled: bool,
+}
+
+const MAX_NAVIGATION_HISTORY_LEN: usize = 1024;
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum NavigationMode {
+    #[default]
+    Normal,
+    GoingBack,
+    GoingForward,
+    ClosingItem,
+    Disabled,
+}
+
+/// A position in the navigation history: an item, and optionally a position in it
+/// to restore with [`Item::navigate`].
+///
+/// [`Item::navigate`]: crate::item::Item::navigate
+pub struct NavigationEntry {
+    pub item: Arc<dyn WeakItemHandle>,
+    pub data: Option<Box<dyn Any + Send>>,
+    pub timestamp: usize,
+}
+
+/// The clock shared by the navigation histories of the panes of a workspace, which orders
+/// their entries as a single timeline.
+#[derive(Default)]
+pub struct NavTimeline {
+    next_timestamp: AtomicUsize,
+    /// The forward entries older than this were recorded before the latest new entry of the
+    /// timeline, in any pane, and can't be navigated to anymore.
+    forward_start: AtomicUsize,
+}
+
+impl NavTimeline {
+    fn next_timestamp(&self) -> usize {
+        self.next_timestamp.fetch_add(1, Ordering::SeqCst)
+    }
+}
+
+/// The history of the items viewed in a pane, for `GoBack` and `GoForward`.
+#[derive(Clone)]
+pub struct NavHistory(Arc<Mutex<NavHistoryState>>);
+
+struct NavHistoryState {
+    mode: NavigationMode,
+    backward_stack: VecDeque<NavigationEntry>,
+    forward_stack: VecDeque<NavigationEntry>,
+    timeline: Arc<NavTimeline>,
+}
+
+impl NavHistoryState {
+    /// The forward stack, without the entries made stale by a new entry in another pane.
+    fn forward_stack(&mut self) -> &mut VecDeque<NavigationEntry> {
+        let forward_start = self.timeline.forward_start.load(Ordering::SeqCst);
+        self.forward_stack
+            .retain(|entry| entry.timestamp >= forward_start);
+        &mut self.forward_stack
+    }
+}
+
+impl NavHistory {
+    fn new(timeline: Arc<NavTimeline>) -> Self {
+        Self(Arc::new(Mutex::new(NavHistoryState {
+            mode: NavigationMode::Normal,
+            backward_stack: VecDeque::new(),
+            forward_stack: VecDeque::new(),
+            timeline,
+        })))
+    }
+
+    pub fn mode(&self) -> NavigationMode {
+        self.0.lock().mode
+    }
+
+    /// Set how new entries are recorded, entries pushed while going back are
+    /// recorded in the forward stack and the other way around.
+    pub fn set_mode(&self, mode: NavigationMode) {
+        self.0.lock().mode = mode;
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.0.lock().backward_stack.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.0.lock().forward_stack().is_empty()
+    }
+
+    /// Record a position of the item according to the current mode.
+    ///
+    /// A new entry, recorded in the normal mode, clears the forward stacks of every pane
+    /// of the timeline.
+    pub fn push<D: Any + Send>(&self, data: Option<D>, item: Arc<dyn WeakItemHandle>) {
+        let state = &mut *self.0.lock();
+        let mode = state.mode;
+        if matches!(mode, NavigationMode::Disabled | NavigationMode::ClosingItem) {
+            return;
+        }
+
+        let timestamp = state.timeline.next_timestamp();
+        let stack = if mode == NavigationMode::GoingBack {
+            state.forward_stack()
+        } else {
+            &mut state.backward_stack
+        };
+
+        // An item leaving without a position is already covered by the last
+        // position it recorded itself, which is only moved to now in the timeline.
+        let last_entry = stack
+            .back_mut()
+            .filter(|entry| data.is_none() && entry.item.id() == item.id());
+        if let Some(entry) = last_entry {
+            entry.timestamp = timestamp;
+        } else {
+            if stack.len() >= MAX_NAVIGATION_HISTORY_LEN {
+                stack.pop_front();
+            }
+            stack.push_back(NavigationEntry {
+                item,
+                data: data.map(|data| Box::new(data) as Box<dyn Any + Send>),
+                timestamp,
+            });
+        }
+
+        if mode == NavigationMode::Normal {
+            state.forward_stack.clear();
+            state
+                .timeline
+                .forward_start
+                .fetch_max(timestamp + 1, Ordering::SeqCst);
+        }
+    }
+
+    /// Returns the timestamp of the entry [`NavHistory::pop`] would return.
+    pub fn last_timestamp(&self, mode: NavigationMode) -> Option<usize> {
+        let mut state = self.0.lock();
+        let entry = match mode {
+            NavigationMode::GoingBack => state.backward_stack.back(),
+            NavigationMode::GoingForward => state.forward_stack().back(),
+            _ => None,
+        };
+        entry.map(|entry| entry.timestamp)
+    }
+
+    /// Take the most recent entry of the stack read in the given mode.
+    pub fn pop(&self, mode: NavigationMode) -> Option<NavigationEntry> {
+        let mut state = self.0.lock();
+        match mode {
+            NavigationMode::GoingBack => state.backward_stack.pop_back(),
+            NavigationMode::GoingForward => state.forward_stack().pop_back(),
+            _ => None,
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.0.lock();
+        state.backward_stack.clear();
+        state.forward_stack.clear();
+    }
+}
+
+/// The navigation history of the pane an item is in, given to the item with
+/// [`Item::set_nav_history`].
+///
+/// [`Item::set_nav_history`]: crate::item::Item::set_nav_history
+#[derive(Clone)]
+pub struct ItemNavHistory {
+    history: NavHistory,
+    item: Arc<dyn WeakItemHandle>,
+}
+
+impl ItemNavHistory {
+    /// Record a position in the item, restored with [`Item::navigate`] on `GoBack`.
+    ///
+    /// [`Item::navigate`]: crate::item::Item::navigate
+    pub fn push<D: Any + Send>(&mut self, data: Option<D>) {
+        self.history.push(data, self.item.clone());
+    }
+
+    pub fn navigation_mode(&self) -> NavigationMode {
+        self.history.mode()
+    }
+}
+
+/// When an item was last activated in a pane, to order the items by most recent use.
+#[derive(Debug, Clone, Copy)]
+pub struct ActivationHistoryEntry {
+    pub entity_id: EntityId,
+    pub timestamp: usize,
+}
+
 #[derive(Clone)]
 pub struct DraggedTab {
     pub pane: Entity<Pane>,
@@ -107,6 +358,7 @@ pub struct DraggedTab {
     pub ix: usize,
     pub detail: usize,
     pub is_active: bool,
+    pub is_pinned: bool,
 }
 
 impl Render for DraggedTab {
@@ -115,14 +367,31 @@ impl Render for DraggedTab {
             TabContentParams {
                 detail: Some(self.detail),
                 selected: false,
+                is_pinned: self.is_pinned,
+                preview: false,
             },
             window,
             cx,
         );
-        Tab::new("", label).selected(self.is_active).into_element()
+        Tab::new("", label)
+            .selected(self.is_active)
+            .prefix(render_tab_icon(self.item.colored_tab_icon(window, cx)))
+            .into_element()
     }
 }
 
+/// Render a tab icon in a fixed size slot, which is left empty without an icon.
+pub(crate) fn render_tab_icon(icon: Option<Icon>) -> AnyElement {
+    div()
+        .flex()
+        .flex_none()
+        .items_center()
+        .justify_center()
+        .size(px(13.))
+        .children(icon.map(|icon| icon.size(px(13.))))
+        .into_any_element()
+}
+
 #[allow(clippy::type_complexity)]
 /// A pane
 pub struct Pane {
@@ -133,6 +402,13 @@ pub struct Pane {
     was_focused: bool,
     last_focus_handle_by_item: HashMap<EntityId, WeakFocusHandle>,
     active_item_index: usize,
+    /// The number of pinned items, which are always the first items of the pane.
+    pinned_tab_count: usize,
+    /// The item replaced by the next preview item, see [`Pane::add_preview_item`].
+    preview_item_id: Option<EntityId>,
+    max_tabs: Option<NonZeroUsize>,
+    /// Closes the items over `max_tabs`, only one runs at a time.
+    close_items_over_max_tabs_task: Option<Task<()>>,
     drag_split_direction: Option<SplitDirection>,
     can_drop_predicate: Option<Arc<dyn Fn(&dyn Any, &mut Window, &mut App) -> bool>>,
     custom_drop_handle: Option<
@@ -141,6 +417,11 @@ pub struct Pane {
     can_split: bool,
     should_display_tab_bar: Rc<dyn Fn(&Window, &Context<Pane>) -> bool>,
     tab_bar_scroll_handle: ScrollHandle,
+    nav_history: NavHistory,
+    activation_history: Vec<ActivationHistoryEntry>,
+    nav_timeline: Arc<NavTimeline>,
+    /// The subscriptions to the events of the items, by item id.
+    item_subscriptions: HashMap<EntityId, Subscription>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -150,6 +431,7 @@ impl Pane {
     #[allow(clippy::type_complexity)]
     pub fn new(
         workspace: WeakEntity<Workspace>,
+        nav_timeline: Arc<NavTimeline>,
         can_drop_predicate: Option<Arc<dyn Fn(&dyn Any, &mut Window, &mut App) -> bool + 'static>>,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -170,6 +452,10 @@ impl Pane {
             zoomed: false,
             was_focused: false,
             active_item_index: 0,
+            pinned_tab_count: 0,
+            preview_item_id: None,
+            max_tabs: None,
+            close_items_over_max_tabs_task: None,
             drag_split_direction: None,
             last_focus_handle_by_item: HashMap::new(),
             can_drop_predicate,
@@ -177,6 +463,10 @@ impl Pane {
             can_split: true,
             should_display_tab_bar: Rc::new(|_, _| true),
             tab_bar_scroll_handle: ScrollHandle::new(),
+            nav_history: NavHistory::new(nav_timeline.clone()),
+            activation_history: Vec::new(),
+            nav_timeline,
+            item_subscriptions: HashMap::new(),
             _subscriptions: subscriptions,
         }
     }
@@ -211,16 +501,34 @@ impl Pane {
         item_id_to_close: EntityId,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Task<Result<()>> {
+    ) -> Task<Result<CloseOutcome>> {
         self.close_items(window, cx, move |view_id| view_id == item_id_to_close)
     }
 
+    /// Close the items matching `should_close` one after the other.
+    ///
+    /// Each item is asked with [`Item::can_close`], and a dirty item is saved or discarded
+    /// after asking the user. Closing stops at the first item that is kept open.
+    ///
+    /// [`Item::can_close`]: crate::item::Item::can_close
     pub fn close_items(
         &mut self,
         window: &mut Window,
         cx: &mut Context<Pane>,
         should_close: impl Fn(EntityId) -> bool,
-    ) -> Task<Result<()>> {
+    ) -> Task<Result<CloseOutcome>> {
+        self.close_items_inner(true, window, cx, should_close)
+    }
+
+    /// Close the items matching `should_close`, emitting [`Event::ItemClosed`] for them when
+    /// `user_closed` is set, so they can be reopened.
+    fn close_items_inner(
+        &mut self,
+        user_closed: bool,
+        window: &mut Window,
+        cx: &mut Context<Pane>,
+        should_close: impl Fn(EntityId) -> bool,
+    ) -> Task<Result<CloseOutcome>> {
         let mut items_to_close = Vec::new();
 
         for item in &self.items {
@@ -231,7 +539,24 @@ impl Pane {
 
         // let workspace = self.workspace.clone();
         cx.spawn_in(window, |pane, mut cx| async move {
+            let mut outcome = CloseOutcome::default();
             for item in items_to_close {
+                let is_open = pane.read_with(&cx, |pane, _| {
+                    pane.index_for_item_id(item.item_id()).is_some()
+                })?;
+                if !is_open {
+                    continue;
+                }
+
+                // Stop closing the remaining items when the item or the user cancels.
+                let can_close = cx.update(|window, cx| item.can_close(window, cx))?.await;
+                if !can_close
+                    || !Self::save_item_before_close(&pane, item.as_ref(), &mut cx).await?
+                {
+                    outcome.cancelled = true;
+                    break;
+                }
+
                 // Remove the item from the pane.
                 pane.update_in(&mut cx, |pane, window, cx| {
                     if let Some(item_ix) = pane
@@ -239,17 +564,86 @@ impl Pane {
                         .iter()
                         .position(|i| i.item_id() == item.item_id())
                     {
+                        if user_closed {
+                            cx.emit(Event::ItemClosed {
+                                item: item.boxed_clone(),
+                                index: item_ix,
+                                is_pinned: pane.is_tab_pinned(item_ix),
+                            });
+                        }
                         pane.remove_item(item_ix, false, window, cx);
+                        outcome.closed += 1;
                     }
                 })
                 .ok();
             }
 
             pane.update(&mut cx, |_, cx| cx.notify()).ok();
-            Ok(())
+            Ok(outcome)
         })
     }
 
+    /// Ask the user whether to save a dirty item before closing it, and save it if asked to.
+    ///
+    /// Returns `false` if the user cancelled closing the item.
+    async fn save_item_before_close(
+        pane: &WeakEntity<Pane>,
+        item: &dyn ItemHandle,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<bool> {
+        let (is_dirty, has_conflict, can_save, title) = cx.update(|_, cx| {
+            (
+                item.is_dirty(cx),
+                item.has_conflict(cx),
+                item.can_save(cx),
+                item.tab_title(cx),
+            )
+        })?;
+        if !is_dirty {
+            return Ok(true);
+        }
+
+        let message = match (has_conflict, title) {
+            (true, Some(title)) => format!("{} has changed since you started editing it.", title),
+            (true, None) => "This item has changed since you started editing it.".to_string(),
+            (false, Some(title)) => format!("Do you want to save the changes to {}?", title),
+            (false, None) => "Do you want to save the changes to this item?".to_string(),
+        };
+        let mut answers = Vec::new();
+        if can_save {
+            answers.push(if has_conflict { "Overwrite" } else { "Save" });
+        }
+        answers.push("Don't Save");
+        answers.push("Cancel");
+
+        let answer = pane.update_in(cx, |pane, window, cx| {
+            // Show the item the prompt is about.
+            if let Some(ix) = pane.index_for_item_id(item.item_id()) {
+                pane.activate_item(ix, true, true, window, cx);
+            }
+            window.prompt(
+                PromptLevel::Warning,
+                &message,
+                Some("Your changes will be lost if you don't save them."),
+                &answers,
+                cx,
+            )
+        })?;
+
+        // The prompt is dropped without an answer when its window is closed.
+        let Ok(answer) = answer.await else {
+            return Ok(false);
+        };
+        match answers.get(answer) {
+            Some(&"Save") | Some(&"Overwrite") => {
+                cx.update(|window, cx| item.save(window, cx))?.await?;
+                Ok(true)
+            }
+            Some(&"Don't Save") => Ok(true),
+            _ => Ok(false),
+        }
+    }
+
     pub fn remove_item(
         &mut self,
         item_index: usize,
@@ -257,9 +651,31 @@ impl Pane {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let close_pane_if_empty = true;
-        // self.activation_history
-        //     .retain(|entry| entry.entity_id != self.items[item_index].item_id());
+        self.remove_item_inner(item_index, activate_pane, true, window, cx);
+    }
+
+    /// Remove the item at `item_index` to add it to another pane, keeping this pane even
+    /// when it becomes empty.
+    pub(crate) fn detach_item(
+        &mut self,
+        item_index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.remove_item_inner(item_index, false, false, window, cx);
+    }
+
+    fn remove_item_inner(
+        &mut self,
+        item_index: usize,
+        activate_pane: bool,
+        close_pane_if_empty: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let item_id = self.items[item_index].item_id();
+        self.activation_history
+            .retain(|entry| entry.entity_id != item_id);
 
         if item_index == self.active_item_index {
             let index_to_activate = item_index.min(self.items.len()).saturating_sub(1);
@@ -268,6 +684,9 @@ impl Pane {
             if self.items.len() == 1 && should_activate {
                 self.focus_handle.focus(window);
             } else {
+                // The item being closed can't be navigated back to.
+                let mode = self.nav_history.mode();
+                self.nav_history.set_mode(NavigationMode::ClosingItem);
                 self.activate_item(
                     index_to_activate,
                     should_activate,
@@ -275,10 +694,18 @@ impl Pane {
                     window,
                     cx,
                 );
+                self.nav_history.set_mode(mode);
             }
         }
 
         let item = self.items.remove(item_index);
+        self.item_subscriptions.remove(&item_id);
+        if self.is_tab_pinned(item_index) {
+            self.pinned_tab_count -= 1;
+        }
+        if self.preview_item_id == Some(item_id) {
+            self.preview_item_id = None;
+        }
         cx.emit(Event::RemoveItem {
             item_id: item.item_id(),
         });
@@ -293,11 +720,6 @@ impl Pane {
             self.active_item_index -= 1;
         }
 
-        // let mode = self.nav_history.mode();
-        // self.nav_history.set_mode(NavigationMode::ClosingItem);
-        // item.deactivated(cx);
-        // self.nav_history.set_mode(mode);
-
         if self.items.is_empty() && close_pane_if_empty && self.zoomed {
             cx.emit(Event::ZoomOut);
         }
@@ -313,6 +735,26 @@ impl Pane {
         destination_index: Option<usize>,
         window: &mut Window,
         cx: &mut Context<Self>,
+    ) {
+        self.add_item_inner(
+            item,
+            activate_pane,
+            focus_item,
+            destination_index,
+            window,
+            cx,
+        );
+        self.close_items_over_max_tabs(window, cx);
+    }
+
+    fn add_item_inner(
+        &mut self,
+        item: Box<dyn ItemHandle>,
+        activate_pane: bool,
+        focus_item: bool,
+        destination_index: Option<usize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
     ) {
         // If no destination index is specified, add or move the item after the active item.
         let mut insertion_index = {
@@ -331,6 +773,13 @@ impl Pane {
             .iter()
             .position(|existing_item| existing_item.item_id() == item.item_id());
 
+        // Keep the pinned items grouped at the start of the pane.
+        if existing_item_index.is_some_and(|ix| self.is_tab_pinned(ix)) {
+            insertion_index = insertion_index.min(self.pinned_tab_count - 1);
+        } else {
+            insertion_index = insertion_index.max(self.pinned_tab_count);
+        }
+
         if let Some(existing_item_index) = existing_item_index {
             // If the item already exists, move it to the desired destination and activate it
 
@@ -362,6 +811,15 @@ impl Pane {
 
             self.activate_item(insertion_index, activate_pane, focus_item, window, cx);
         } else {
+            item.set_nav_history(
+                ItemNavHistory {
+                    history: self.nav_history.clone(),
+                    item: Arc::from(item.downgrade_item()),
+                },
+                window,
+                cx,
+            );
+            self.subscribe_to_item(item.as_ref(), window, cx);
             self.items.insert(insertion_index, item.clone());
 
             if insertion_index <= self.active_item_index {
@@ -375,6 +833,157 @@ impl Pane {
         cx.emit(Event::AddItem { item });
     }
 
+    fn subscribe_to_item(
+        &mut self,
+        item: &dyn ItemHandle,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let pane = cx.entity().downgrade();
+        let item_id = item.item_id();
+        let subscription = item.subscribe_to_item_events(
+            window,
+            cx,
+            Box::new(move |event, _, cx| {
+                if let ItemEvent::Edit = event {
+                    pane.update(cx, |pane, cx| {
+                        pane.unpreview_item_if_preview(item_id, cx);
+                        // Update the dirty indicator of the tab.
+                        cx.notify();
+                    })
+                    .ok();
+                }
+            }),
+        );
+        self.item_subscriptions.insert(item_id, subscription);
+    }
+
+    /// Add an item as the preview item of the pane, replacing the current preview item
+    /// rather than adding another tab.
+    ///
+    /// The preview item becomes a regular item once edited or when its tab is double-clicked.
+    /// An item that is already open in the pane is activated and keeps its tab. The replaced
+    /// preview item is closed like with [`Pane::close_items`], without being reopenable.
+    pub fn add_preview_item(
+        &mut self,
+        item: Box<dyn ItemHandle>,
+        activate_pane: bool,
+        focus_item: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.index_for_item_id(item.item_id()).is_some() {
+            self.add_item(item, activate_pane, focus_item, None, window, cx);
+            return;
+        }
+
+        let item_id = item.item_id();
+        let preview_item_index = self.preview_item_index();
+        let replaced_item_id = preview_item_index.map(|ix| self.items[ix].item_id());
+        // Add the new item first, so the pane isn't removed for being empty.
+        let destination_index = preview_item_index.map(|ix| ix + 1);
+        self.add_item_inner(
+            item,
+            activate_pane,
+            focus_item,
+            destination_index,
+            window,
+            cx,
+        );
+        self.preview_item_id = Some(item_id);
+        if let Some(replaced_item_id) = replaced_item_id {
+            // The replaced item can refuse to close or have unsaved changes, in which case it
+            // stays open as a regular item.
+            self.close_items_inner(false, window, cx, move |item_id| {
+                item_id == replaced_item_id
+            })
+            .detach_and_log_err(cx);
+        }
+        self.close_items_over_max_tabs(window, cx);
+        cx.notify();
+    }
+
+    pub fn max_tabs(&self) -> Option<NonZeroUsize> {
+        self.max_tabs
+    }
+
+    /// Limit the number of items of the pane, `None` to allow any number of items.
+    ///
+    /// When the limit is exceeded, the least recently activated items are closed, except for
+    /// the active, pinned and dirty items.
+    pub fn set_max_tabs(
+        &mut self,
+        max_tabs: Option<NonZeroUsize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.max_tabs = max_tabs;
+        self.close_items_over_max_tabs(window, cx);
+    }
+
+    /// Close the least recently activated items over the limit of the pane.
+    ///
+    /// The evicted items aren't recorded as closed by the user. While they are being closed,
+    /// the items added in the meantime are checked once the close is done.
+    fn close_items_over_max_tabs(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(max_tabs) = self.max_tabs else {
+            return;
+        };
+        if self.close_items_over_max_tabs_task.is_some() {
+            return;
+        }
+
+        let active_item_id = self.active_item().map(|item| item.item_id());
+        let items = self
+            .items
+            .iter()
+            .enumerate()
+            .map(|(ix, item)| {
+                let can_evict = Some(item.item_id()) != active_item_id
+                    && !self.is_tab_pinned(ix)
+                    && !item.is_dirty(cx);
+                (item.item_id(), can_evict)
+            })
+            .collect::<Vec<_>>();
+        let items_to_close = items_to_evict(&items, &self.activation_history, max_tabs.get());
+        if items_to_close.is_empty() {
+            return;
+        }
+
+        let close = self.close_items_inner(false, window, cx, move |item_id| {
+            items_to_close.contains(&item_id)
+        });
+        self.close_items_over_max_tabs_task =
+            Some(cx.spawn_in(window, |pane, mut cx| async move {
+                let outcome = close.await.log_err();
+                pane.update_in(&mut cx, |pane, window, cx| {
+                    pane.close_items_over_max_tabs_task = None;
+                    // An item refusing to close would be asked again on every check.
+                    if outcome.is_some_and(|outcome| !outcome.cancelled) {
+                        pane.close_items_over_max_tabs(window, cx);
+                    }
+                })
+                .ok();
+            }));
+    }
+
+    pub fn preview_item_id(&self) -> Option<EntityId> {
+        self.preview_item_id
+    }
+
+    fn preview_item_index(&self) -> Option<usize> {
+        self.preview_item_id
+            .and_then(|item_id| self.index_for_item_id(item_id))
+    }
+
+    /// Make the item a regular item if it is the preview item of the pane.
+    pub fn unpreview_item_if_preview(&mut self, item_id: EntityId, cx: &mut Context<Self>) {
+        if self.preview_item_id == Some(item_id) {
+            self.preview_item_id = None;
+            cx.notify();
+        }
+    }
+
     pub fn items_len(&self) -> usize {
         self.items.len()
     }
@@ -387,6 +996,61 @@ impl Pane {
         self.items.get(self.active_item_index).cloned()
     }
 
+    pub fn index_for_item_id(&self, item_id: EntityId) -> Option<usize> {
+        self.items.iter().position(|item| item.item_id() == item_id)
+    }
+
+    /// Returns when the items were last activated, the most recent last.
+    pub fn activation_history(&self) -> &[ActivationHistoryEntry] {
+        &self.activation_history
+    }
+
+    pub fn nav_history(&self) -> &NavHistory {
+        &self.nav_history
+    }
+
+    pub fn can_navigate_backward(&self) -> bool {
+        self.nav_history.can_go_back()
+    }
+
+    pub fn can_navigate_forward(&self) -> bool {
+        self.nav_history.can_go_forward()
+    }
+
+    pub fn reopen_closed_item(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.defer_workspace_update(window, cx, |workspace, window, cx| {
+            workspace
+                .reopen_closed_item(window, cx)
+                .detach_and_log_err(cx);
+        });
+    }
+
+    /// Go back in the navigation timeline of the whole workspace, see [`Workspace::go_back`].
+    pub fn go_back(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.defer_workspace_update(window, cx, |workspace, window, cx| {
+            workspace.go_back(window, cx);
+        });
+    }
+
+    /// Go forward in the navigation timeline of the whole workspace, see [`Workspace::go_forward`].
+    pub fn go_forward(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.defer_workspace_update(window, cx, |workspace, window, cx| {
+            workspace.go_forward(window, cx);
+        });
+    }
+
+    /// Update the workspace once the current update of the pane is done.
+    fn defer_workspace_update(
+        &self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        f: impl FnOnce(&mut Workspace, &mut Window, &mut Context<Workspace>) + 'static,
+    ) {
+        self.workspace
+            .update(cx, |_, cx| cx.defer_in(window, f))
+            .log_err();
+    }
+
     pub fn pixel_position_of_cursor(&self, cx: &App) -> Option<Point<Pixels>> {
         self.items
             .get(self.active_item_index)?
@@ -402,10 +1066,20 @@ impl Pane {
         cx: &mut Context<Self>,
     ) {
         if index < self.items.len() {
+            let item_id = self.items[index].item_id();
+            self.activation_history
+                .retain(|entry| entry.entity_id != item_id);
+            self.activation_history.push(ActivationHistoryEntry {
+                entity_id: item_id,
+                timestamp: self.nav_timeline.next_timestamp(),
+            });
+
             let prev_active_item_ix = mem::replace(&mut self.active_item_index, index);
             if prev_active_item_ix != self.active_item_index {
                 if let Some(prev_item) = self.items.get(prev_active_item_ix) {
                     prev_item.deactivated(window, cx);
+                    self.nav_history
+                        .push(None::<()>, Arc::from(prev_item.downgrade_item()));
                 }
             }
 
@@ -457,7 +1131,7 @@ impl Pane {
         _: &CloseActiveItem,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Option<Task<Result<()>>> {
+    ) -> Option<Task<Result<CloseOutcome>>> {
         if self.items.is_empty() {
             // cx.dispatch_action(Box::new(CloseWindow));
             return None;
@@ -472,13 +1146,16 @@ impl Pane {
         _: &CloseInactiveItems,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Option<Task<Result<()>>> {
+    ) -> Option<Task<Result<CloseOutcome>>> {
         if self.items.is_empty() {
             return None;
         }
 
         let active_item_id = self.items[self.active_item_index].item_id();
-        Some(self.close_items(window, cx, move |item_id| item_id != active_item_id))
+        let pinned_item_ids = self.pinned_item_ids();
+        Some(self.close_items(window, cx, move |item_id| {
+            item_id != active_item_id && !pinned_item_ids.contains(&item_id)
+        }))
     }
 
     pub fn close_items_to_the_left(
@@ -486,7 +1163,7 @@ impl Pane {
         _: &CloseItemsToTheLeft,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Option<Task<Result<()>>> {
+    ) -> Option<Task<Result<CloseOutcome>>> {
         if self.items.is_empty() {
             return None;
         }
@@ -499,13 +1176,16 @@ impl Pane {
         item_id: EntityId,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Task<Result<()>> {
+    ) -> Task<Result<CloseOutcome>> {
         let item_ids: Vec<_> = self
             .items()
             .take_while(|item| item.item_id() != item_id)
             .map(|item| item.item_id())
             .collect();
-        self.close_items(window, cx, move |item_id| item_ids.contains(&item_id))
+        let pinned_item_ids = self.pinned_item_ids();
+        self.close_items(window, cx, move |item_id| {
+            item_ids.contains(&item_id) && !pinned_item_ids.contains(&item_id)
+        })
     }
 
     pub fn close_items_to_the_right(
@@ -513,7 +1193,7 @@ impl Pane {
         _: &CloseItemsToTheRight,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Option<Task<Result<()>>> {
+    ) -> Option<Task<Result<CloseOutcome>>> {
         if self.items.is_empty() {
             return None;
         }
@@ -526,14 +1206,17 @@ impl Pane {
         item_id: EntityId,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Task<Result<()>> {
+    ) -> Task<Result<CloseOutcome>> {
         let item_ids: Vec<_> = self
             .items()
             .rev()
             .take_while(|item| item.item_id() != item_id)
             .map(|item| item.item_id())
             .collect();
-        self.close_items(window, cx, move |item_id| item_ids.contains(&item_id))
+        let pinned_item_ids = self.pinned_item_ids();
+        self.close_items(window, cx, move |item_id| {
+            item_ids.contains(&item_id) && !pinned_item_ids.contains(&item_id)
+        })
     }
 
     pub fn close_all_items(
@@ -541,12 +1224,127 @@ impl Pane {
         _: &CloseAllItems,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Option<Task<Result<()>>> {
+    ) -> Option<Task<Result<CloseOutcome>>> {
         if self.items.is_empty() {
             return None;
         }
 
-        Some(self.close_items(window, cx, |_| true))
+        let pinned_item_ids = self.pinned_item_ids();
+        Some(self.close_items(window, cx, move |item_id| {
+            !pinned_item_ids.contains(&item_id)
+        }))
+    }
+
+    /// Returns the number of pinned items, which are the first items of the pane.
+    pub fn pinned_count(&self) -> usize {
+        self.pinned_tab_count
+    }
+
+    pub(crate) fn set_pinned_count(&mut self, count: usize) {
+        self.pinned_tab_count = count.min(self.items.len());
+    }
+
+    pub fn is_tab_pinned(&self, ix: usize) -> bool {
+        ix < self.pinned_tab_count
+    }
+
+    fn pinned_item_ids(&self) -> Vec<EntityId> {
+        self.items
+            .iter()
+            .take(self.pinned_tab_count)
+            .map(|item| item.item_id())
+            .collect()
+    }
+
+    /// Pin the item at `ix`, moving it to the end of the pinned items.
+    ///
+    /// Pinned items stay at the start of the pane and are left open by the bulk close actions.
+    pub fn pin_tab_at(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if ix >= self.items.len() || self.is_tab_pinned(ix) {
+            return;
+        }
+
+        let item_id = self.items[ix].item_id();
+        self.unpreview_item_if_preview(item_id, cx);
+        let destination_index = self.pinned_tab_count;
+        self.pinned_tab_count += 1;
+        self.move_item_within(ix, destination_index);
+        cx.emit(Event::ItemPinned { item_id });
+        cx.notify();
+    }
+
+    /// Unpin the item at `ix`, moving it right after the pinned items.
+    pub fn unpin_tab_at(&mut self, ix: usize, cx: &mut Context<Self>) {
+        if !self.is_tab_pinned(ix) {
+            return;
+        }
+
+        let item_id = self.items[ix].item_id();
+        self.pinned_tab_count -= 1;
+        let destination_index = self.pinned_tab_count;
+        self.move_item_within(ix, destination_index);
+        cx.emit(Event::ItemUnpinned { item_id });
+        cx.notify();
+    }
+
+    fn pin_tab(&mut self, _: &PinTab, _: &mut Window, cx: &mut Context<Self>) {
+        self.pin_tab_at(self.active_item_index, cx);
+    }
+
+    fn unpin_tab(&mut self, _: &UnpinTab, _: &mut Window, cx: &mut Context<Self>) {
+        self.unpin_tab_at(self.active_item_index, cx);
+    }
+
+    fn toggle_pin_tab(&mut self, _: &TogglePinTab, _: &mut Window, cx: &mut Context<Self>) {
+        if self.is_tab_pinned(self.active_item_index) {
+            self.unpin_tab_at(self.active_item_index, cx);
+        } else {
+            self.pin_tab_at(self.active_item_index, cx);
+        }
+    }
+
+    fn copy_tab_title(&mut self, _: &CopyTabTitle, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(title) = self.active_item().and_then(|item| item.tab_title(cx)) {
+            cx.write_to_clipboard(ClipboardItem::new_string(title.to_string()));
+        }
+    }
+
+    fn move_item_to_new_window(
+        &mut self,
+        _: &MoveItemToNewWindow,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(item_id) = self.active_item().map(|item| item.item_id()) else {
+            return;
+        };
+        let pane = cx.entity();
+        self.defer_workspace_update(window, cx, move |workspace, window, cx| {
+            workspace
+                .move_item_to_new_window(pane, item_id, None, window, cx)
+                .log_err();
+        });
+    }
+
+    /// Move an item to another index of this pane, the same item stays active.
+    fn move_item_within(&mut self, from: usize, to: usize) {
+        if from == to {
+            return;
+        }
+
+        let item = self.items.remove(from);
+        self.items.insert(to, item);
+
+        let active = self.active_item_index;
+        self.active_item_index = if active == from {
+            to
+        } else if from < active && active <= to {
+            active - 1
+        } else if to <= active && active < from {
+            active + 1
+        } else {
+            active
+        };
     }
 
     pub fn focus(&mut self, window: &mut Window) {
@@ -651,7 +1449,12 @@ impl Pane {
                     if let Some(split_direction) = split_direction {
                         to_pane = workspace.split_pane(to_pane, split_direction, window, cx);
                     }
-                    workspace.move_item(from_pane, to_pane, item_id, ix, window, cx);
+                    let moved =
+                        workspace.move_item(from_pane, to_pane.clone(), item_id, ix, window, cx);
+                    if moved.log_err().is_none() && split_direction.is_some() {
+                        // Don't leave the split made for the item empty.
+                        workspace.remove_pane(&to_pane, window, cx);
+                    }
                 });
             })
             .log_err();
@@ -700,52 +1503,108 @@ impl Pane {
         cx: &mut Context<'_, Pane>,
     ) -> impl IntoElement {
         let is_active = ix == self.active_item_index;
+        let is_pinned = self.is_tab_pinned(ix);
+        let is_dirty = item.is_dirty(cx);
+        let item_id = item.item_id();
+        let icon = item.colored_tab_icon(window, cx);
 
         let label = item.tab_content(
             TabContentParams {
                 detail: Some(detail),
                 selected: is_active,
+                is_pinned,
+                preview: self.preview_item_id == Some(item_id),
             },
             window,
             cx,
         );
 
-        let item_id = item.item_id();
         let _is_first_item = ix == 0;
         let _is_last_item = ix == self.items.len() - 1;
         let _position_relative_to_active_item = ix.cmp(&self.active_item_index);
 
-        Tab::new(ix, label)
+        let tab = Tab::new(ix, label)
             .group("tab")
-            .px(px(5.))
-            .prefix(div().size(px(13.)).into_any_element())
-            .gap_1p5()
-            .suffix(
-                div()
-                    .id("close-tab")
-                    .p(px(0.))
-                    .rounded_sm()
-                    .invisible()
-                    .child(Icon::new(IconName::Close).size(px(12.)))
-                    .hover(|this| this.bg(cx.theme().accent.darken(0.1)))
-                    .active(|this| this.bg(cx.theme().accent.darken(0.2)))
-                    .on_click(cx.listener(move |pane, _, window, cx| {
-                        pane.close_item_by_id(item_id, window, cx)
-                            .detach_and_log_err(cx);
-                    }))
-                    .group_hover("tab", |this| this.visible())
-                    .into_any(),
-            )
+            .map(|tab| {
+                // Pinned tabs are compact, and can't be closed from the tab bar.
+                if is_pinned {
+                    return tab
+                        .px(px(3.))
+                        .gap_1()
+                        .when_some(icon, |tab, icon| {
+                            tab.prefix(icon.size(px(13.)).into_any_element())
+                        })
+                        .when(is_dirty, |tab| {
+                            tab.suffix(
+                                div()
+                                    .size(px(6.))
+                                    .rounded_full()
+                                    .bg(cx.theme().foreground)
+                                    .into_any_element(),
+                            )
+                        });
+                }
+
+                tab.px(px(5.))
+                    .prefix(render_tab_icon(icon))
+                    .gap_1p5()
+                    .suffix(
+                        div()
+                            .id("close-tab")
+                            .relative()
+                            .p(px(0.))
+                            .rounded_sm()
+                            .when(!is_dirty, |this| this.invisible())
+                            // Dirty items show a dot, replaced by the close button on hover.
+                            .when(is_dirty, |this| {
+                                this.child(
+                                    div()
+                                        .absolute()
+                                        .size_full()
+                                        .flex()
+                                        .items_center()
+                                        .justify_center()
+                                        .group_hover("tab", |this| this.invisible())
+                                        .child(
+                                            div()
+                                                .size(px(6.))
+                                                .rounded_full()
+                                                .bg(cx.theme().foreground),
+                                        ),
+                                )
+                            })
+                            .child(
+                                div()
+                                    .when(is_dirty, |this| this.invisible())
+                                    .group_hover("tab", |this| this.visible())
+                                    .child(Icon::new(IconName::Close).size(px(12.))),
+                            )
+                            .hover(|this| this.bg(cx.theme().accent.darken(0.1)))
+                            .active(|this| this.bg(cx.theme().accent.darken(0.2)))
+                            .on_click(cx.listener(move |pane, _, window, cx| {
+                                pane.close_item_by_id(item_id, window, cx)
+                                    .detach_and_log_err(cx);
+                            }))
+                            .group_hover("tab", |this| this.visible())
+                            .into_any(),
+                    )
+            })
             .selected(is_active)
-            .on_click(cx.listener(move |pane: &mut Self, _, window, cx| {
-                pane.activate_item(ix, true, true, window, cx)
-            }))
+            .on_click(
+                cx.listener(move |pane: &mut Self, event: &ClickEvent, window, cx| {
+                    if event.up.click_count > 1 {
+                        pane.unpreview_item_if_preview(item_id, cx);
+                    }
+                    pane.activate_item(ix, true, true, window, cx)
+                }),
+            )
             .on_drag(
                 DraggedTab {
                     item: item.boxed_clone(),
                     pane: cx.entity().clone(),
                     detail,
                     is_active,
+                    is_pinned,
                     ix,
                 },
                 |tab, _, _, cx| cx.new(|_| tab.clone()),
@@ -768,6 +1627,49 @@ impl Pane {
             )
             .when_some(item.tab_tooltip(cx), |tab, text| {
                 tab.tooltip(move |window, cx| Tooltip::new(text.clone(), window, cx))
+            });
+
+        let menu_item = item.boxed_clone();
+        let has_title = item.tab_title(cx).is_some();
+        let can_split = self.can_split;
+
+        div()
+            .id(ix)
+            .child(tab)
+            .on_mouse_down(
+                MouseButton::Right,
+                cx.listener(move |pane, _, window, cx| {
+                    pane.activate_item(ix, true, true, window, cx);
+                }),
+            )
+            // The tab is activated by the right click, so the actions apply to its item.
+            .context_menu(move |menu, window, cx| {
+                let mut menu = menu
+                    .menu("Close", Box::new(CloseActiveItem))
+                    .menu("Close Others", Box::new(CloseInactiveItems))
+                    .menu("Close Left", Box::new(CloseItemsToTheLeft))
+                    .menu("Close Right", Box::new(CloseItemsToTheRight))
+                    .menu("Close All", Box::new(CloseAllItems))
+                    .separator();
+                if can_split {
+                    menu = menu
+                        .menu("Split Left", Box::new(SplitLeft))
+                        .menu("Split Up", Box::new(SplitUp))
+                        .menu("Split Right", Box::new(SplitRight))
+                        .menu("Split Down", Box::new(SplitDown))
+                        .separator();
+                }
+                menu = menu.menu(
+                    if is_pinned { "Unpin Tab" } else { "Pin Tab" },
+                    Box::new(TogglePinTab),
+                );
+                if has_title {
+                    menu = menu.menu("Copy Tab Title", Box::new(CopyTabTitle));
+                }
+                menu = menu
+                    .separator()
+                    .menu("Move to New Window", Box::new(MoveItemToNewWindow));
+                menu_item.tab_context_menu(menu, window, cx)
             })
     }
 
@@ -776,28 +1678,45 @@ impl Pane {
         window: &mut Window,
         cx: &mut Context<'_, Pane>,
     ) -> impl IntoElement {
-        //     let navigate_backward = IconButton::new("navigate_backward", IconName::ArrowLeft)
-        //     .shape(IconButtonShape::Square)
-        //     .icon_size(IconSize::Small)
-        //     .on_click({
-        //         let view = cx.view().clone();
-        //         move |_, cx| view.update(cx, Self::navigate_backward)
-        //     })
-        //     .disabled(!self.can_navigate_backward())
-        //     .tooltip(|cx| Tooltip::for_action("Go Back", &GoBack, cx));
-
-        // let navigate_forward = IconButton::new("navigate_forward", IconName::ArrowRight)
-        //     .shape(IconButtonShape::Square)
-        //     .icon_size(IconSize::Small)
-        //     .on_click({
-        //         let view = cx.view().clone();
-        //         move |_, cx| view.update(cx, Self::navigate_forward)
-        //     })
-        //     .disabled(!self.can_navigate_forward())
-        //     .tooltip(|cx| Tooltip::for_action("Go Forward", &GoForward, cx));
+        let (can_go_back, can_go_forward) = self
+            .workspace
+            .upgrade()
+            .map(|workspace| {
+                let workspace = workspace.read(cx);
+                (workspace.can_go_back(cx), workspace.can_go_forward(cx))
+            })
+            .unwrap_or_default();
+
+        let navigate_backward = Button::new("navigate-backward")
+            .icon(IconName::ArrowLeft)
+            .ghost()
+            .xsmall()
+            .disabled(!can_go_back)
+            .tooltip("Go Back")
+            .on_click(cx.listener(|pane, _, window, cx| pane.go_back(window, cx)));
+
+        let navigate_forward = Button::new("navigate-forward")
+            .icon(IconName::ArrowRight)
+            .ghost()
+            .xsmall()
+            .disabled(!can_go_forward)
+            .tooltip("Go Forward")
+            .on_click(cx.listener(|pane, _, window, cx| pane.go_forward(window, cx)));
+
+        // The scroll handle is laid out in the previous frame, the tabs overflow when it
+        // can scroll.
+        let is_overflowing = self.tab_bar_scroll_handle.max_offset().width > px(0.);
+        let pane = cx.entity().downgrade();
 
         TabBar::new("tab-bar")
             .track_scroll(self.tab_bar_scroll_handle.clone())
+            .prefix(
+                h_flex()
+                    .px_1()
+                    .gap_0p5()
+                    .child(navigate_backward)
+                    .child(navigate_forward),
+            )
             .children(
                 self.items
                     .iter()
@@ -821,6 +1740,25 @@ impl Pane {
                         }),
                     ),
             )
+            .when(is_overflowing, |tab_bar| {
+                tab_bar.suffix(
+                    h_flex().px_1().child(
+                        Popover::new("tab-overflow-menu")
+                            .anchor(Corner::TopRight)
+                            .trigger(
+                                Button::new("tab-overflow")
+                                    .icon(IconName::Ellipsis)
+                                    .ghost()
+                                    .xsmall()
+                                    .tooltip("Show All Tabs"),
+                            )
+                            .content(move |window, cx| {
+                                let pane = pane.clone();
+                                cx.new(|cx| tab_overflow_menu::build(pane, window, cx))
+                            }),
+                    ),
+                )
+            })
     }
 
     pub fn set_zoomed(&mut self, zoomed: bool, _window: &mut Window, cx: &mut Context<Self>) {
@@ -837,6 +1775,31 @@ impl Pane {
     }
 }
 
+/// Returns the items to close to keep at most `max_tabs` items, given the id of every item
+/// and whether it can be closed, the least recently activated first.
+///
+/// The activation history is ordered from the least recently activated item, items that
+/// were never activated are closed first.
+fn items_to_evict(
+    items: &[(EntityId, bool)],
+    activation_history: &[ActivationHistoryEntry],
+    max_tabs: usize,
+) -> Vec<EntityId> {
+    let excess = items.len().saturating_sub(max_tabs);
+    let mut candidates = items
+        .iter()
+        .filter(|(_, can_evict)| *can_evict)
+        .map(|(item_id, _)| *item_id)
+        .collect::<Vec<_>>();
+    candidates.sort_by_key(|item_id| {
+        activation_history
+            .iter()
+            .position(|entry| entry.entity_id == *item_id)
+    });
+    candidates.truncate(excess);
+    candidates
+}
+
 pub fn tab_details(items: &Vec<Box<dyn ItemHandle>>, cx: &App) -> Vec<usize> {
     let mut tab_details = items.iter().map(|_| 0).collect::<Vec<_>>();
     let mut tab_descriptions = HashMap::new();
@@ -908,8 +1871,16 @@ impl Render for Pane {
             .on_action(cx.listener(|pane, _: &SplitDown, window, cx| {
                 pane.split(SplitDirection::Down, window, cx)
             }))
-            // .on_action(cx.listener(|pane, _: &GoBack, window, cx| pane.navigate_backward(window, cx)))
-            // .on_action(cx.listener(|pane, _: &GoForward, window, cx| pane.navigate_forward(window, cx)))
+            .on_action(cx.listener(|pane, _: &GoBack, window, cx| pane.go_back(window, cx)))
+            .on_action(cx.listener(|pane, _: &GoForward, window, cx| pane.go_forward(window, cx)))
+            .on_action(cx.listener(|pane, _: &ReopenClosedItem, window, cx| {
+                pane.reopen_closed_item(window, cx)
+            }))
+            .on_action(cx.listener(Pane::pin_tab))
+            .on_action(cx.listener(Pane::unpin_tab))
+            .on_action(cx.listener(Pane::toggle_pin_tab))
+            .on_action(cx.listener(Pane::copy_tab_title))
+            .on_action(cx.listener(Pane::move_item_to_new_window))
             // .on_action(cx.listener(Pane::toggle_zoom))
             .on_action(
                 cx.listener(|pane: &mut Pane, action: &ActivateItem, window, cx| {
@@ -1038,32 +2009,14 @@ impl Render for Pane {
                             }),
                     )
             })
-        // .on_mouse_down(
-        //     MouseButton::Navigate(NavigationDirection::Back),
-        //     cx.listener(|pane, _, cx| {
-        //         if let Some(workspace) = pane.workspace.upgrade() {
-        //             let pane = cx.view().downgrade();
-        //             cx.window_context().defer(move |cx| {
-        //                 workspace.update(cx, |workspace, cx| {
-        //                     workspace.go_back(pane, cx).detach_and_log_err(cx)
-        //                 })
-        //             })
-        //         }
-        //     }),
-        // )
-        // .on_mouse_down(
-        //     MouseButton::Navigate(NavigationDirection::Forward),
-        //     cx.listener(|pane, _, cx| {
-        //         if let Some(workspace) = pane.workspace.upgrade() {
-        //             let pane = cx.view().downgrade();
-        //             cx.window_context().defer(move |cx| {
-        //                 workspace.update(cx, |workspace, cx| {
-        //                     workspace.go_forward(pane, cx).detach_and_log_err(cx)
-        //                 })
-        //             })
-        //         }
-        //     }),
-        // )
+            .on_mouse_down(
+                MouseButton::Navigate(NavigationDirection::Back),
+                cx.listener(|pane, _, window, cx| pane.go_back(window, cx)),
+            )
+            .on_mouse_down(
+                MouseButton::Navigate(NavigationDirection::Forward),
+                cx.listener(|pane, _, window, cx| pane.go_forward(window, cx)),
+            )
     }
 }
 
@@ -1079,3 +2032,155 @@ pub struct DraggedSelection {
 pub struct SelectedEntry {
     pub entry_id: EntityId,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct TestItem(u64);
+
+    impl WeakItemHandle for TestItem {
+        fn id(&self) -> EntityId {
+            EntityId::from(self.0)
+        }
+
+        fn upgrade(&self) -> Option<Box<dyn ItemHandle>> {
+            None
+        }
+    }
+
+    fn item(item_id: u64) -> Arc<dyn WeakItemHandle> {
+        Arc::new(TestItem(item_id))
+    }
+
+    fn pop_all(history: &NavHistory, mode: NavigationMode) -> Vec<u64> {
+        std::iter::from_fn(|| history.pop(mode))
+            .map(|entry| entry.item.id().as_u64())
+            .collect()
+    }
+
+    #[test]
+    fn test_nav_history_ordering() {
+        let history = NavHistory::new(Arc::default());
+        for item_id in [1, 2, 3] {
+            history.push(Some(item_id), item(item_id));
+        }
+
+        // Entries pushed while going back are read when going forward.
+        history.set_mode(NavigationMode::GoingBack);
+        let entry = history.pop(NavigationMode::GoingBack).unwrap();
+        assert_eq!(entry.item.id(), EntityId::from(3));
+        history.push(None::<()>, item(4));
+        history.set_mode(NavigationMode::Normal);
+
+        assert!(history.can_go_forward());
+        assert_eq!(pop_all(&history, NavigationMode::GoingForward), [4]);
+        assert_eq!(pop_all(&history, NavigationMode::GoingBack), [2, 1]);
+    }
+
+    #[test]
+    fn test_nav_history_push_clears_forward_stack() {
+        let history = NavHistory::new(Arc::default());
+        history.set_mode(NavigationMode::GoingBack);
+        history.push(None::<()>, item(1));
+        history.set_mode(NavigationMode::Disabled);
+        history.push(None::<()>, item(2));
+        assert!(history.can_go_forward());
+        assert!(!history.can_go_back());
+
+        history.set_mode(NavigationMode::Normal);
+        history.push(None::<()>, item(3));
+        assert!(!history.can_go_forward());
+        assert_eq!(pop_all(&history, NavigationMode::GoingBack), [3]);
+    }
+
+    #[test]
+    fn test_nav_history_timestamps() {
+        let timeline = Arc::<NavTimeline>::default();
+        let left = NavHistory::new(timeline.clone());
+        let right = NavHistory::new(timeline);
+
+        left.push(None::<()>, item(1));
+        right.push(None::<()>, item(2));
+        assert!(
+            left.last_timestamp(NavigationMode::GoingBack)
+                < right.last_timestamp(NavigationMode::GoingBack)
+        );
+
+        // Leaving the same item again only moves its entry to now.
+        left.push(None::<()>, item(1));
+        assert!(
+            left.last_timestamp(NavigationMode::GoingBack)
+                > right.last_timestamp(NavigationMode::GoingBack)
+        );
+        assert_eq!(pop_all(&left, NavigationMode::GoingBack), [1]);
+
+        // A new position in the same item is its own entry.
+        right.push(Some(10), item(2));
+        assert_eq!(pop_all(&right, NavigationMode::GoingBack), [2, 2]);
+    }
+
+    #[test]
+    fn test_nav_history_push_clears_forward_stacks_of_timeline() {
+        let timeline = Arc::<NavTimeline>::default();
+        let left = NavHistory::new(timeline.clone());
+        let right = NavHistory::new(timeline);
+        for (history, item_id) in [(&left, 1), (&right, 2)] {
+            history.set_mode(NavigationMode::GoingBack);
+            history.push(None::<()>, item(item_id));
+            history.set_mode(NavigationMode::Normal);
+        }
+        assert!(left.can_go_forward());
+
+        right.push(None::<()>, item(3));
+        assert!(!left.can_go_forward());
+        assert!(!right.can_go_forward());
+        assert_eq!(left.last_timestamp(NavigationMode::GoingForward), None);
+
+        // The entries recorded afterwards can be navigated to.
+        left.set_mode(NavigationMode::GoingBack);
+        left.push(None::<()>, item(4));
+        assert_eq!(pop_all(&left, NavigationMode::GoingForward), [4]);
+    }
+
+    fn activated(item_ids: &[u64]) -> Vec<ActivationHistoryEntry> {
+        item_ids
+            .iter()
+            .enumerate()
+            .map(|(timestamp, item_id)| ActivationHistoryEntry {
+                entity_id: EntityId::from(*item_id),
+                timestamp,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_items_to_evict() {
+        let items = [1, 2, 3, 4]
+            .map(|item_id| (EntityId::from(item_id), true))
+            .to_vec();
+
+        assert!(items_to_evict(&items, &activated(&[3, 1, 4, 2]), 4).is_empty());
+        assert_eq!(
+            items_to_evict(&items, &activated(&[3, 1, 4, 2]), 2),
+            [EntityId::from(3), EntityId::from(1)]
+        );
+        // Items that were never activated go first.
+        assert_eq!(
+            items_to_evict(&items, &activated(&[3, 1, 4]), 3),
+            [EntityId::from(2)]
+        );
+    }
+
+    #[test]
+    fn test_items_to_evict_skips_kept_items() {
+        let items = [(1, true), (2, false), (3, true), (4, false)]
+            .map(|(item_id, can_evict)| (EntityId::from(item_id), can_evict))
+            .to_vec();
+
+        assert_eq!(
+            items_to_evict(&items, &activated(&[2, 3, 4, 1]), 1),
+            [EntityId::from(3), EntityId::from(1)]
+        );
+    }
+}
@@ -30,6 +30,10 @@ impl PaneGroup {
         }
     }
 
+    pub(crate) fn with_root(root: Member) -> Self {
+        Self { root }
+    }
+
     pub fn split(
         &mut self,
         old_pane: &Entity<Pane>,
@@ -103,7 +107,6 @@ impl PaneGroup {
         panes
     }
 
-    #[allow(unused)]
     pub(crate) fn first_pane(&self) -> Entity<Pane> {
         self.root.first_pane()
     }
@@ -210,7 +213,6 @@ impl PaneAxis {
         }
     }
 
-    #[allow(unused)]
     pub fn load(axis: Axis, members: Vec<Member>, flexes: Option<Vec<f32>>) -> Self {
         let flexes = flexes.unwrap_or_else(|| vec![1.; members.len()]);
         debug_assert!(members.len() == flexes.len());
@@ -386,6 +388,20 @@ impl PaneAxis {
     }
 }
 
+/// Scale the flexes of an axis so that they sum up to the number of members,
+/// which is what the pane axis element expects.
+///
+/// Returns `None` if any of the flexes is not a positive, finite number.
+pub(crate) fn normalize_flexes(flexes: &[f32]) -> Option<Vec<f32>> {
+    if flexes.is_empty() || flexes.iter().any(|flex| !flex.is_finite() || *flex <= 0.) {
+        return None;
+    }
+
+    let total = flexes.iter().sum::<f32>();
+    let len = flexes.len() as f32;
+    Some(flexes.iter().map(|flex| flex * len / total).collect())
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
 pub enum SplitDirection {
     Up,
@@ -865,3 +881,21 @@ mod element {
         (flexes.iter().copied().sum::<f32>() - flexes.len() as f32).abs() < 0.001
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_flexes() {
+        assert_eq!(normalize_flexes(&[1., 1.]), Some(vec![1., 1.]));
+        assert_eq!(normalize_flexes(&[3., 1.]), Some(vec![1.5, 0.5]));
+        assert_eq!(normalize_flexes(&[2., 2., 4.]), Some(vec![0.75, 0.75, 1.5]));
+
+        assert_eq!(normalize_flexes(&[]), None);
+        assert_eq!(normalize_flexes(&[1., 0.]), None);
+        assert_eq!(normalize_flexes(&[1., -1.]), None);
+        assert_eq!(normalize_flexes(&[1., f32::NAN]), None);
+        assert_eq!(normalize_flexes(&[1., f32::INFINITY]), None);
+    }
+}
@@ -0,0 +1,240 @@
+use anyhow::{anyhow, Context as _, Result};
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::model::SerializedWorkspace;
+
+/// The version of the layout format written by this version of the crate.
+pub const LAYOUT_VERSION: u32 = 2;
+
+/// Upgrades a layout from the version at its index + 1 to the next version.
+type Migration = fn(&mut Value) -> Result<()>;
+
+const MIGRATIONS: &[Migration] = &[migrate_v1_to_v2];
+
+/// The top-level format of a saved layout.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VersionedLayout {
+    /// The version of the layout format, layouts without a version are version 1.
+    pub version: u32,
+    #[serde(flatten)]
+    pub workspace: SerializedWorkspace,
+}
+
+/// Returns the JSON Schema of the layout format, for editor tooling.
+pub fn layout_schema() -> RootSchema {
+    schema_for!(VersionedLayout)
+}
+
+/// Serialize a layout with the current version of the format.
+pub fn serialize_layout(workspace: &SerializedWorkspace) -> Result<Value> {
+    Ok(serde_json::to_value(VersionedLayout {
+        version: LAYOUT_VERSION,
+        workspace: workspace.clone(),
+    })?)
+}
+
+/// Deserialize a layout written by any version of the crate, migrating it to the current format.
+pub fn deserialize_layout(mut layout: Value) -> Result<SerializedWorkspace> {
+    let version = match layout.get("version") {
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| anyhow!("invalid layout version: {}", version))?,
+        None => 1,
+    };
+
+    if version == 0 || version > LAYOUT_VERSION {
+        return Err(anyhow!(
+            "unsupported layout version {}, expected at most {}",
+            version,
+            LAYOUT_VERSION
+        ));
+    }
+
+    for (ix, migration) in MIGRATIONS.iter().enumerate().skip(version as usize - 1) {
+        migration(&mut layout)
+            .with_context(|| format!("failed to migrate layout to version {}", ix + 2))?;
+    }
+
+    let layout: VersionedLayout = serde_json::from_value(layout)?;
+    Ok(layout.workspace)
+}
+
+/// Version 2 keeps the size of every panel in `panels`, and requires items to have a kind.
+fn migrate_v1_to_v2(layout: &mut Value) -> Result<()> {
+    fn migrate_pane_group(group: &mut Value) {
+        let is_pane = group.get("type").and_then(Value::as_str) == Some("pane");
+        if let Some(children) = group.get_mut("children").and_then(Value::as_array_mut) {
+            if is_pane {
+                // Items written before items had a kind can't be restored.
+                children.retain(|item| item.get("kind").is_some_and(Value::is_string));
+            } else {
+                children.iter_mut().for_each(migrate_pane_group);
+            }
+        }
+    }
+
+    fn migrate_dock(dock: &mut Value) {
+        let Some(dock) = dock.as_object_mut() else {
+            return;
+        };
+
+        let size = dock.remove("size").filter(|size| size.is_number());
+        let visible = dock
+            .get("visible")
+            .and_then(Value::as_bool)
+            .unwrap_or(false);
+        let active_panel = dock
+            .get("active_panel")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        if let Some((size, active_panel)) = size.zip(active_panel) {
+            let panels = dock
+                .entry("panels")
+                .or_insert_with(|| Value::Object(Default::default()));
+            if let Some(panels) = panels.as_object_mut() {
+                let panel = panels.entry(active_panel).or_insert_with(
+                    || serde_json::json!({ "open": visible, "active": true, "zoom": false }),
+                );
+                if let Some(panel) = panel.as_object_mut() {
+                    if let None | Some(Value::Null) = panel.get("size") {
+                        panel.insert("size".into(), size);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(center_group) = layout.get_mut("center_group") {
+        migrate_pane_group(center_group);
+    }
+
+    if let Some(docks) = layout.get_mut("docks").and_then(Value::as_object_mut) {
+        docks.values_mut().for_each(migrate_dock);
+    }
+
+    if let Some(layout) = layout.as_object_mut() {
+        layout.insert("version".into(), 2.into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::persistence::model::{
+        DockData, DockStructure, SerializedAxis, SerializedItem, SerializedPane,
+        SerializedPaneGroup, SerializedPanel,
+    };
+
+    #[test]
+    fn test_migrate_v1_to_v2() {
+        let layout = json!({
+            "center_group": {
+                "type": "group",
+                "axis": "horizontal",
+                "flexes": null,
+                "children": [
+                    {
+                        "type": "pane",
+                        "active": true,
+                        "children": [
+                            { "kind": "editor", "item_id": 1, "active": true },
+                            { "item_id": 2, "active": false },
+                        ],
+                    },
+                    { "type": "pane", "active": false, "children": [] },
+                ],
+            },
+            "docks": {
+                "left": { "visible": true, "active_panel": "files", "zoom": false, "size": 240.0 },
+                "right": { "visible": false, "active_panel": null, "zoom": false, "size": 300.0 },
+                "bottom": { "visible": false, "active_panel": null, "zoom": false },
+            },
+        });
+
+        let workspace = deserialize_layout(layout).unwrap();
+        assert_eq!(
+            workspace.center_group,
+            SerializedPaneGroup::Group {
+                axis: SerializedAxis::Horizontal,
+                flexes: None,
+                children: vec![
+                    SerializedPaneGroup::Pane(SerializedPane::new(
+                        vec![SerializedItem::new("editor", 1, true, None)],
+                        true,
+                    )),
+                    SerializedPaneGroup::Pane(SerializedPane::new(Vec::new(), false)),
+                ],
+            }
+        );
+        assert_eq!(
+            workspace.docks.left.panels.get("files"),
+            Some(&SerializedPanel {
+                size: Some(240.),
+                zoom: false,
+                open: true,
+                active: true,
+                state: None,
+            })
+        );
+        // A dock without an active panel has no panel to keep the size for.
+        assert!(workspace.docks.right.panels.is_empty());
+    }
+
+    #[test]
+    fn test_reject_unknown_version() {
+        let mut layout = serialize_layout(&SerializedWorkspace::default()).unwrap();
+        layout["version"] = (LAYOUT_VERSION + 1).into();
+        assert!(deserialize_layout(layout.clone()).is_err());
+
+        layout["version"] = 0.into();
+        assert!(deserialize_layout(layout.clone()).is_err());
+
+        layout["version"] = "2".into();
+        assert!(deserialize_layout(layout).is_err());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let workspace = SerializedWorkspace {
+            center_group: SerializedPaneGroup::Pane(SerializedPane {
+                active: true,
+                children: vec![
+                    SerializedItem::new("editor", 1, false, Some(json!({ "path": "a.rs" }))),
+                    SerializedItem::new("editor", 2, true, None),
+                ],
+                pinned_count: 1,
+            }),
+            docks: DockStructure {
+                left: DockData {
+                    visible: true,
+                    active_panel: Some("files".into()),
+                    zoom: false,
+                    panels: [(
+                        "files".to_string(),
+                        SerializedPanel {
+                            size: Some(240.),
+                            zoom: false,
+                            open: true,
+                            active: true,
+                            state: Some(json!({ "expanded": ["src"] })),
+                        },
+                    )]
+                    .into_iter()
+                    .collect(),
+                },
+                ..Default::default()
+            },
+        };
+
+        let layout = serialize_layout(&workspace).unwrap();
+        assert_eq!(layout["version"], json!(LAYOUT_VERSION));
+        assert_eq!(deserialize_layout(layout).unwrap(), workspace);
+    }
+}
@@ -0,0 +1,482 @@
+mod migrations;
+pub mod model;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{Context as _, Result};
+use gpui::{point, size, App, Bounds, DisplayId, Global, Pixels, WindowBounds};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{util::ResultExt, WorkspaceId};
+
+use self::model::{DisplayWindowBounds, SerializedWindowBounds, SerializedWorkspace};
+
+pub use migrations::{
+    deserialize_layout, layout_schema, serialize_layout, VersionedLayout, LAYOUT_VERSION,
+};
+
+/// A storage backend for the workspace state.
+///
+/// The methods are called from the background executor, so implementations are
+/// free to block on IO.
+pub trait WorkspacePersistence: Send + Sync {
+    /// Save the layout of the workspace.
+    fn save_workspace(&self, id: WorkspaceId, workspace: &SerializedWorkspace) -> Result<()>;
+    /// Load the layout of the workspace, if one was saved.
+    fn load_workspace(&self, id: WorkspaceId) -> Result<Option<SerializedWorkspace>>;
+    /// Save the bounds of the workspace window on the display with the given UUID.
+    fn save_window_bounds(
+        &self,
+        id: WorkspaceId,
+        display_uuid: String,
+        bounds: SerializedWindowBounds,
+    ) -> Result<()>;
+    /// Returns the saved window bounds of the workspace for every display,
+    /// the most recently saved first.
+    fn window_bounds(&self, id: WorkspaceId) -> Result<Vec<DisplayWindowBounds>>;
+    /// Mark the workspace as used now.
+    fn update_timestamp(&self, id: WorkspaceId) -> Result<()>;
+    /// Returns the ids of the saved workspaces, most recently used first.
+    fn recent_workspaces(&self) -> Result<Vec<WorkspaceId>>;
+}
+
+struct GlobalWorkspacePersistence(Arc<dyn WorkspacePersistence>);
+
+impl Global for GlobalWorkspacePersistence {}
+
+/// Set the backend used by every workspace to save and restore its state.
+pub fn init(persistence: impl WorkspacePersistence + 'static, cx: &mut App) {
+    cx.set_global(GlobalWorkspacePersistence(Arc::new(persistence)));
+}
+
+/// Returns the backend set with [`init`], if any.
+pub fn persistence(cx: &App) -> Option<Arc<dyn WorkspacePersistence>> {
+    cx.try_global::<GlobalWorkspacePersistence>()
+        .map(|persistence| persistence.0.clone())
+}
+
+/// Returns the bounds and display to reopen the window of the workspace where it was last
+/// closed.
+///
+/// When the display the window was last on is no longer connected, the window is reopened
+/// on the primary display, with the bounds saved for it if there are any, otherwise centered
+/// with its last size.
+pub fn restore_window_bounds(
+    id: WorkspaceId,
+    cx: &App,
+) -> Option<(WindowBounds, Option<DisplayId>)> {
+    let saved_bounds = persistence(cx)?.window_bounds(id).log_err()?;
+    let last_bounds = saved_bounds.first()?;
+
+    let displays = cx.displays();
+    let display_for_uuid = |uuid: &str| {
+        displays.iter().find(|display| {
+            display
+                .uuid()
+                .map_or(false, |display_uuid| display_uuid.to_string() == uuid)
+        })
+    };
+
+    if let Some(display) = display_for_uuid(&last_bounds.display_uuid) {
+        return Some((last_bounds.bounds.into(), Some(display.id())));
+    }
+
+    let Some(primary_display) = cx.primary_display() else {
+        return Some((last_bounds.bounds.into(), None));
+    };
+
+    if let Some(primary_bounds) = saved_bounds.iter().find(|saved| {
+        primary_display
+            .uuid()
+            .map_or(false, |uuid| uuid.to_string() == saved.display_uuid)
+    }) {
+        return Some((primary_bounds.bounds.into(), Some(primary_display.id())));
+    }
+
+    let display_bounds = primary_display.bounds();
+    let center_in_display = |bounds: Bounds<Pixels>| {
+        let size = size(
+            bounds.size.width.min(display_bounds.size.width),
+            bounds.size.height.min(display_bounds.size.height),
+        );
+        let origin = point(
+            display_bounds.origin.x + (display_bounds.size.width - size.width) / 2.,
+            display_bounds.origin.y + (display_bounds.size.height - size.height) / 2.,
+        );
+        Bounds { origin, size }
+    };
+
+    let bounds = match WindowBounds::from(last_bounds.bounds) {
+        WindowBounds::Windowed(bounds) => WindowBounds::Windowed(center_in_display(bounds)),
+        WindowBounds::Maximized(bounds) => WindowBounds::Maximized(center_in_display(bounds)),
+        WindowBounds::Fullscreen(bounds) => WindowBounds::Fullscreen(center_in_display(bounds)),
+    };
+    Some((bounds, Some(primary_display.id())))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PersistedWorkspace {
+    id: WorkspaceId,
+    /// The layout in the versioned format, see [`serialize_layout`].
+    #[serde(default)]
+    layout: Option<serde_json::Value>,
+    /// The window bounds for each display, the most recently saved first.
+    #[serde(default)]
+    window_bounds: Vec<DisplayWindowBounds>,
+    #[serde(default)]
+    timestamp: u64,
+}
+
+impl PersistedWorkspace {
+    fn save_layout(&mut self, workspace: &SerializedWorkspace) -> Result<()> {
+        self.layout = Some(serialize_layout(workspace)?);
+        Ok(())
+    }
+
+    fn load_layout(&self) -> Result<Option<SerializedWorkspace>> {
+        self.layout.clone().map(deserialize_layout).transpose()
+    }
+
+    fn save_window_bounds(&mut self, display_uuid: String, bounds: SerializedWindowBounds) {
+        self.window_bounds
+            .retain(|saved| saved.display_uuid != display_uuid);
+        self.window_bounds.insert(
+            0,
+            DisplayWindowBounds {
+                display_uuid,
+                bounds,
+            },
+        );
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct PersistedState {
+    workspaces: Vec<PersistedWorkspace>,
+}
+
+impl PersistedState {
+    fn workspace(&self, id: WorkspaceId) -> Option<&PersistedWorkspace> {
+        self.workspaces.iter().find(|workspace| workspace.id == id)
+    }
+
+    fn load_layout(&self, id: WorkspaceId) -> Result<Option<SerializedWorkspace>> {
+        match self.workspace(id) {
+            Some(workspace) => workspace.load_layout(),
+            None => Ok(None),
+        }
+    }
+
+    fn workspace_mut(&mut self, id: WorkspaceId) -> &mut PersistedWorkspace {
+        let ix = match self
+            .workspaces
+            .iter()
+            .position(|workspace| workspace.id == id)
+        {
+            Some(ix) => ix,
+            None => {
+                self.workspaces.push(PersistedWorkspace {
+                    id,
+                    layout: None,
+                    window_bounds: Vec::new(),
+                    timestamp: now(),
+                });
+                self.workspaces.len() - 1
+            }
+        };
+        &mut self.workspaces[ix]
+    }
+
+    fn window_bounds(&self, id: WorkspaceId) -> Vec<DisplayWindowBounds> {
+        self.workspace(id)
+            .map(|workspace| workspace.window_bounds.clone())
+            .unwrap_or_default()
+    }
+
+    fn recent_workspaces(&self) -> Vec<WorkspaceId> {
+        let mut workspaces = self.workspaces.iter().collect::<Vec<_>>();
+        workspaces.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        workspaces
+            .into_iter()
+            .map(|workspace| workspace.id)
+            .collect()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_millis() as u64)
+}
+
+/// A backend that keeps the workspace state in memory, useful for tests.
+#[derive(Default)]
+pub struct InMemoryPersistence {
+    state: Mutex<PersistedState>,
+}
+
+impl InMemoryPersistence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl WorkspacePersistence for InMemoryPersistence {
+    fn save_workspace(&self, id: WorkspaceId, workspace: &SerializedWorkspace) -> Result<()> {
+        self.state.lock().workspace_mut(id).save_layout(workspace)
+    }
+
+    fn load_workspace(&self, id: WorkspaceId) -> Result<Option<SerializedWorkspace>> {
+        self.state.lock().load_layout(id)
+    }
+
+    fn save_window_bounds(
+        &self,
+        id: WorkspaceId,
+        display_uuid: String,
+        bounds: SerializedWindowBounds,
+    ) -> Result<()> {
+        self.state
+            .lock()
+            .workspace_mut(id)
+            .save_window_bounds(display_uuid, bounds);
+        Ok(())
+    }
+
+    fn window_bounds(&self, id: WorkspaceId) -> Result<Vec<DisplayWindowBounds>> {
+        Ok(self.state.lock().window_bounds(id))
+    }
+
+    fn update_timestamp(&self, id: WorkspaceId) -> Result<()> {
+        self.state.lock().workspace_mut(id).timestamp = now();
+        Ok(())
+    }
+
+    fn recent_workspaces(&self) -> Result<Vec<WorkspaceId>> {
+        Ok(self.state.lock().recent_workspaces())
+    }
+}
+
+/// A backend that stores the workspace state of all workspaces in a single JSON file.
+pub struct JsonFilePersistence {
+    path: PathBuf,
+    state: Mutex<PersistedState>,
+}
+
+impl JsonFilePersistence {
+    /// Open the JSON file at `path`, the file is created on the first save if it doesn't exist.
+    ///
+    /// A file that can't be parsed is logged and replaced on the next save.
+    pub fn new(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let state = if path.exists() {
+            let content = fs::read_to_string(&path)
+                .with_context(|| format!("failed to read {}", path.display()))?;
+            serde_json::from_str(&content)
+                .with_context(|| format!("failed to parse {}", path.display()))
+                .log_err()
+                .unwrap_or_default()
+        } else {
+            PersistedState::default()
+        };
+
+        Ok(Self {
+            path,
+            state: Mutex::new(state),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn update(&self, f: impl FnOnce(&mut PersistedState) -> Result<()>) -> Result<()> {
+        let mut state = self.state.lock();
+        f(&mut state)?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first, so a crash never leaves a truncated file behind.
+        let tmp_path = self.path.with_extension("json.tmp");
+        fs::write(&tmp_path, serde_json::to_string_pretty(&*state)?)?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("failed to write {}", self.path.display()))?;
+        Ok(())
+    }
+}
+
+impl WorkspacePersistence for JsonFilePersistence {
+    fn save_workspace(&self, id: WorkspaceId, workspace: &SerializedWorkspace) -> Result<()> {
+        self.update(|state| state.workspace_mut(id).save_layout(workspace))
+    }
+
+    fn load_workspace(&self, id: WorkspaceId) -> Result<Option<SerializedWorkspace>> {
+        self.state.lock().load_layout(id)
+    }
+
+    fn save_window_bounds(
+        &self,
+        id: WorkspaceId,
+        display_uuid: String,
+        bounds: SerializedWindowBounds,
+    ) -> Result<()> {
+        self.update(|state| {
+            state
+                .workspace_mut(id)
+                .save_window_bounds(display_uuid, bounds);
+            Ok(())
+        })
+    }
+
+    fn window_bounds(&self, id: WorkspaceId) -> Result<Vec<DisplayWindowBounds>> {
+        Ok(self.state.lock().window_bounds(id))
+    }
+
+    fn update_timestamp(&self, id: WorkspaceId) -> Result<()> {
+        self.update(|state| {
+            state.workspace_mut(id).timestamp = now();
+            Ok(())
+        })
+    }
+
+    fn recent_workspaces(&self) -> Result<Vec<WorkspaceId>> {
+        Ok(self.state.lock().recent_workspaces())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{model::SerializedBounds, *};
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "gpui-workspace-{}-{}.json",
+            name,
+            std::process::id()
+        ))
+    }
+
+    fn bounds(x: f32) -> SerializedWindowBounds {
+        SerializedWindowBounds::Windowed(SerializedBounds {
+            x,
+            y: 0.,
+            width: 800.,
+            height: 600.,
+        })
+    }
+
+    fn assert_round_trip(persistence: &dyn WorkspacePersistence) {
+        let id = WorkspaceId::from(1);
+        let mut workspace = SerializedWorkspace::default();
+        workspace.docks.left.visible = true;
+        workspace.docks.left.active_panel = Some("project".into());
+
+        assert_eq!(persistence.load_workspace(id).unwrap(), None);
+        persistence.save_workspace(id, &workspace).unwrap();
+        assert_eq!(persistence.load_workspace(id).unwrap(), Some(workspace));
+        assert_eq!(
+            persistence.load_workspace(WorkspaceId::from(2)).unwrap(),
+            None
+        );
+
+        persistence
+            .save_window_bounds(id, "display-1".into(), bounds(1.))
+            .unwrap();
+        persistence
+            .save_window_bounds(id, "display-2".into(), bounds(2.))
+            .unwrap();
+        persistence
+            .save_window_bounds(id, "display-1".into(), bounds(3.))
+            .unwrap();
+        // The most recently saved display goes first, with one entry per display.
+        assert_eq!(
+            persistence.window_bounds(id).unwrap(),
+            vec![
+                DisplayWindowBounds {
+                    display_uuid: "display-1".into(),
+                    bounds: bounds(3.),
+                },
+                DisplayWindowBounds {
+                    display_uuid: "display-2".into(),
+                    bounds: bounds(2.),
+                },
+            ]
+        );
+
+        persistence.update_timestamp(WorkspaceId::from(2)).unwrap();
+        let mut recent = persistence.recent_workspaces().unwrap();
+        recent.sort();
+        assert_eq!(recent, vec![WorkspaceId::from(1), WorkspaceId::from(2)]);
+    }
+
+    #[test]
+    fn test_in_memory_round_trip() {
+        assert_round_trip(&InMemoryPersistence::new());
+    }
+
+    #[test]
+    fn test_json_file_round_trip() {
+        let path = temp_path("round-trip");
+        fs::remove_file(&path).ok();
+
+        let persistence = JsonFilePersistence::new(&path).unwrap();
+        assert_round_trip(&persistence);
+
+        // A new backend on the same file sees everything that was saved.
+        let reopened = JsonFilePersistence::new(&path).unwrap();
+        assert_eq!(
+            reopened.load_workspace(WorkspaceId::from(1)).unwrap(),
+            persistence.load_workspace(WorkspaceId::from(1)).unwrap()
+        );
+        assert_eq!(
+            reopened.window_bounds(WorkspaceId::from(1)).unwrap(),
+            persistence.window_bounds(WorkspaceId::from(1)).unwrap()
+        );
+        assert_eq!(
+            reopened.recent_workspaces().unwrap(),
+            persistence.recent_workspaces().unwrap()
+        );
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_recent_workspaces() {
+        let workspace = |id: i64, timestamp| PersistedWorkspace {
+            id: WorkspaceId::from(id),
+            layout: None,
+            window_bounds: Vec::new(),
+            timestamp,
+        };
+        let state = PersistedState {
+            workspaces: vec![workspace(1, 20), workspace(2, 30), workspace(3, 10)],
+        };
+
+        assert_eq!(
+            state.recent_workspaces(),
+            vec![
+                WorkspaceId::from(2),
+                WorkspaceId::from(1),
+                WorkspaceId::from(3)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_open_unparsable_file() {
+        let path = temp_path("unparsable");
+        fs::write(&path, "not json").unwrap();
+
+        let persistence = JsonFilePersistence::new(&path).unwrap();
+        assert_eq!(persistence.recent_workspaces().unwrap(), Vec::new());
+
+        fs::remove_file(&path).ok();
+    }
+}
@@ -0,0 +1,376 @@
+use std::{collections::BTreeMap, future::Future, pin::Pin, rc::Rc};
+
+use anyhow::Result;
+use gpui::{
+    point, px, size, App, AsyncWindowContext, Axis, Bounds, Entity, Pixels, Task, WeakEntity,
+    Window, WindowBounds,
+};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    item::ItemHandle,
+    pane::Pane,
+    pane_group::{normalize_flexes, Member, PaneAxis},
+    util::ResultExt,
+    Workspace,
+};
+
+/// The id of an item at the time it was serialized.
+pub type ItemId = u64;
+
+/// A snapshot of the workspace layout: the center pane tree and the state of the docks.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SerializedWorkspace {
+    pub center_group: SerializedPaneGroup,
+    pub docks: DockStructure,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DockStructure {
+    pub left: DockData,
+    pub right: DockData,
+    pub bottom: DockData,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DockData {
+    pub visible: bool,
+    /// The persistent name of the active panel.
+    pub active_panel: Option<String>,
+    pub zoom: bool,
+    /// The state of each panel, keyed by its persistent name.
+    #[serde(default)]
+    pub panels: BTreeMap<String, SerializedPanel>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SerializedPanel {
+    /// The size of the panel, in pixels.
+    pub size: Option<f32>,
+    pub zoom: bool,
+    /// Whether the panel was shown in an open dock.
+    pub open: bool,
+    /// Whether the panel was the active panel of its dock.
+    pub active: bool,
+    /// The state returned by [`Panel::serialize_state`].
+    ///
+    /// [`Panel::serialize_state`]: crate::dock::Panel::serialize_state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_json::Value>,
+}
+
+/// The bounds of a window, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SerializedBounds {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl From<Bounds<Pixels>> for SerializedBounds {
+    fn from(bounds: Bounds<Pixels>) -> Self {
+        Self {
+            x: bounds.origin.x.0,
+            y: bounds.origin.y.0,
+            width: bounds.size.width.0,
+            height: bounds.size.height.0,
+        }
+    }
+}
+
+impl From<SerializedBounds> for Bounds<Pixels> {
+    fn from(bounds: SerializedBounds) -> Self {
+        Bounds {
+            origin: point(px(bounds.x), px(bounds.y)),
+            size: size(px(bounds.width), px(bounds.height)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "state", rename_all = "lowercase")]
+pub enum SerializedWindowBounds {
+    Windowed(SerializedBounds),
+    Maximized(SerializedBounds),
+    Fullscreen(SerializedBounds),
+}
+
+impl From<WindowBounds> for SerializedWindowBounds {
+    fn from(bounds: WindowBounds) -> Self {
+        match bounds {
+            WindowBounds::Windowed(bounds) => Self::Windowed(bounds.into()),
+            WindowBounds::Maximized(bounds) => Self::Maximized(bounds.into()),
+            WindowBounds::Fullscreen(bounds) => Self::Fullscreen(bounds.into()),
+        }
+    }
+}
+
+impl From<SerializedWindowBounds> for WindowBounds {
+    fn from(bounds: SerializedWindowBounds) -> Self {
+        match bounds {
+            SerializedWindowBounds::Windowed(bounds) => Self::Windowed(bounds.into()),
+            SerializedWindowBounds::Maximized(bounds) => Self::Maximized(bounds.into()),
+            SerializedWindowBounds::Fullscreen(bounds) => Self::Fullscreen(bounds.into()),
+        }
+    }
+}
+
+/// The bounds of a workspace window on a display.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DisplayWindowBounds {
+    pub display_uuid: String,
+    pub bounds: SerializedWindowBounds,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SerializedAxis {
+    Horizontal,
+    Vertical,
+}
+
+impl From<Axis> for SerializedAxis {
+    fn from(axis: Axis) -> Self {
+        match axis {
+            Axis::Horizontal => Self::Horizontal,
+            Axis::Vertical => Self::Vertical,
+        }
+    }
+}
+
+impl From<SerializedAxis> for Axis {
+    fn from(axis: SerializedAxis) -> Self {
+        match axis {
+            SerializedAxis::Horizontal => Self::Horizontal,
+            SerializedAxis::Vertical => Self::Vertical,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum SerializedPaneGroup {
+    Group {
+        axis: SerializedAxis,
+        flexes: Option<Vec<f32>>,
+        children: Vec<SerializedPaneGroup>,
+    },
+    Pane(SerializedPane),
+}
+
+impl SerializedPaneGroup {
+    /// Rebuild the pane tree described by this group, creating a pane for every
+    /// serialized pane that still has items after restoring them.
+    ///
+    /// Returns the root member and the pane that was active when serialized.
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn deserialize<'a>(
+        self,
+        workspace: WeakEntity<Workspace>,
+        restore_item: RestoreItem,
+        cx: &'a mut AsyncWindowContext,
+    ) -> Pin<Box<dyn Future<Output = Option<(Member, Option<Entity<Pane>>)>> + 'a>> {
+        Box::pin(async move {
+            match self {
+                SerializedPaneGroup::Group {
+                    axis,
+                    flexes,
+                    children,
+                } => {
+                    let mut current_active_pane = None;
+                    let mut members = Vec::new();
+                    let mut member_flexes = Vec::new();
+                    for (ix, child) in children.into_iter().enumerate() {
+                        if let Some((member, active_pane)) = child
+                            .deserialize(workspace.clone(), restore_item.clone(), cx)
+                            .await
+                        {
+                            members.push(member);
+                            member_flexes.push(flexes.as_ref().and_then(|f| f.get(ix).copied()));
+                            current_active_pane = current_active_pane.or(active_pane);
+                        }
+                    }
+
+                    if members.is_empty() {
+                        return None;
+                    }
+
+                    if members.len() == 1 {
+                        return Some((members.remove(0), current_active_pane));
+                    }
+
+                    let flexes = member_flexes
+                        .into_iter()
+                        .collect::<Option<Vec<_>>>()
+                        .and_then(|flexes| normalize_flexes(&flexes));
+
+                    Some((
+                        Member::Axis(PaneAxis::load(axis.into(), members, flexes)),
+                        current_active_pane,
+                    ))
+                }
+                SerializedPaneGroup::Pane(serialized_pane) => {
+                    let pane = workspace
+                        .update_in(cx, |workspace, window, cx| {
+                            workspace.add_pane(window, cx).downgrade()
+                        })
+                        .log_err()?;
+                    let active = serialized_pane.active;
+                    serialized_pane
+                        .deserialize_to(&pane, restore_item, cx)
+                        .await
+                        .log_err()?;
+
+                    let pane = pane.upgrade()?;
+                    if pane.update(cx, |pane, _| pane.items_len() != 0).log_err()? {
+                        Some((Member::Pane(pane.clone()), active.then_some(pane)))
+                    } else {
+                        workspace
+                            .update_in(cx, |workspace, window, cx| {
+                                workspace.force_remove_pane(&pane, window, cx)
+                            })
+                            .log_err()?;
+                        None
+                    }
+                }
+            }
+        })
+    }
+
+    /// Returns the panes of this group, in depth-first order.
+    pub fn panes(&self) -> Vec<&SerializedPane> {
+        match self {
+            SerializedPaneGroup::Group { children, .. } => {
+                children.iter().flat_map(|child| child.panes()).collect()
+            }
+            SerializedPaneGroup::Pane(pane) => vec![pane],
+        }
+    }
+
+    /// Returns the panes of this group mutably, in depth-first order.
+    pub fn panes_mut(&mut self) -> Vec<&mut SerializedPane> {
+        match self {
+            SerializedPaneGroup::Group { children, .. } => children
+                .iter_mut()
+                .flat_map(|child| child.panes_mut())
+                .collect(),
+            SerializedPaneGroup::Pane(pane) => vec![pane],
+        }
+    }
+}
+
+impl Default for SerializedPaneGroup {
+    fn default() -> Self {
+        Self::Pane(SerializedPane::default())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SerializedPane {
+    pub active: bool,
+    pub children: Vec<SerializedItem>,
+    /// The number of pinned items, which are the first `pinned_count` children.
+    #[serde(default)]
+    pub pinned_count: usize,
+}
+
+impl SerializedPane {
+    pub fn new(children: Vec<SerializedItem>, active: bool) -> Self {
+        Self {
+            children,
+            active,
+            pinned_count: 0,
+        }
+    }
+
+    /// Restore the items of this pane into `pane`, in their serialized order,
+    /// and activate the item that was active when serialized.
+    ///
+    /// Items that fail to restore are skipped.
+    pub(crate) async fn deserialize_to(
+        &self,
+        pane: &WeakEntity<Pane>,
+        restore_item: RestoreItem,
+        cx: &mut AsyncWindowContext,
+    ) -> Result<()> {
+        let mut item_tasks = Vec::new();
+        for item in self.children.iter() {
+            let pane = pane.clone();
+            item_tasks.push(cx.update(|window, cx| restore_item(item, pane, window, cx))?);
+        }
+
+        let mut active_item_index = None;
+        let mut pinned_count = 0;
+        for (ix, (item, task)) in self.children.iter().zip(item_tasks).enumerate() {
+            let Some(item_handle) = task.await.log_err() else {
+                continue;
+            };
+            if ix < self.pinned_count {
+                pinned_count += 1;
+            }
+
+            let index = pane.update_in(cx, |pane, window, cx| {
+                let index = pane.items_len();
+                pane.add_item(item_handle, false, false, Some(index), window, cx);
+                index
+            })?;
+
+            if item.active {
+                active_item_index = Some(index);
+            }
+        }
+
+        pane.update(cx, |pane, _| pane.set_pinned_count(pinned_count))?;
+
+        if let Some(active_item_index) = active_item_index {
+            pane.update_in(cx, |pane, window, cx| {
+                pane.activate_item(active_item_index, false, false, window, cx);
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Recreate an item from its serialized form, to be added into the given pane.
+pub type RestoreItem = Rc<
+    dyn Fn(
+        &SerializedItem,
+        WeakEntity<Pane>,
+        &mut Window,
+        &mut App,
+    ) -> Task<Result<Box<dyn ItemHandle>>>,
+>;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct SerializedItem {
+    /// The [`SerializableItem::serialized_item_kind`] of the item.
+    ///
+    /// [`SerializableItem::serialized_item_kind`]: crate::item::SerializableItem::serialized_item_kind
+    pub kind: String,
+    pub item_id: ItemId,
+    pub active: bool,
+    /// The state returned by [`SerializableItem::serialize`].
+    ///
+    /// [`SerializableItem::serialize`]: crate::item::SerializableItem::serialize
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<serde_json::Value>,
+}
+
+impl SerializedItem {
+    pub fn new(
+        kind: impl Into<String>,
+        item_id: ItemId,
+        active: bool,
+        state: Option<serde_json::Value>,
+    ) -> Self {
+        Self {
+            kind: kind.into(),
+            item_id,
+            active,
+            state,
+        }
+    }
+}
@@ -0,0 +1,29 @@
+//! The dropdown of the tab bar listing every item of a pane, shown when the tabs overflow.
+
+use gpui::{px, Context, WeakEntity, Window};
+
+use crate::{
+    pane::Pane,
+    tab_picker::{TabPicker, TabPickerEntry},
+};
+
+/// Build the menu listing the items of `pane`, activating the chosen one.
+pub(crate) fn build(
+    pane: WeakEntity<Pane>,
+    window: &mut Window,
+    cx: &mut Context<TabPicker>,
+) -> TabPicker {
+    let entries = pane
+        .upgrade()
+        .map(|pane| TabPickerEntry::for_pane(&pane, None, cx))
+        .unwrap_or_default();
+
+    TabPicker::new(
+        entries,
+        |entry, _, window, cx| entry.activate(window, cx),
+        window,
+        cx,
+    )
+    .width(px(300.))
+    .max_height(px(320.))
+}
@@ -0,0 +1,385 @@
+//! The searchable list of tabs shared by the tab search and the tab overflow menu.
+//!
+//! The owner of a picker only gives the entries to list and what to do with the chosen one.
+
+use gpui::{
+    actions, div, prelude::FluentBuilder as _, px, App, AppContext as _, Context, DismissEvent,
+    Entity, EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyBinding,
+    ParentElement as _, Pixels, Render, SharedString, StatefulInteractiveElement as _, Styled as _,
+    Subscription, WeakEntity, Window,
+};
+use ui::{
+    h_flex,
+    input::{InputEvent, TextInput},
+    label::Label,
+    theme::ActiveTheme,
+    v_flex,
+};
+
+use crate::{
+    item::{ItemHandle, TabContentParams},
+    pane::{self, render_tab_icon, Pane},
+};
+
+const CONTEXT: &str = "TabPicker";
+/// The keys of the picker are bound on its query input, whose own bindings would win otherwise.
+const INPUT_CONTEXT: &str = "TabPicker > Input";
+
+actions!(
+    tab_picker,
+    [
+        SelectNext,
+        SelectPrevious,
+        Confirm,
+        SecondaryConfirm,
+        Dismiss
+    ]
+);
+
+/// Bind the default keys of the tab pickers, the tab search and the tab overflow menu.
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("down", SelectNext, Some(INPUT_CONTEXT)),
+        KeyBinding::new("up", SelectPrevious, Some(INPUT_CONTEXT)),
+        KeyBinding::new("enter", Confirm, Some(INPUT_CONTEXT)),
+        KeyBinding::new("secondary-enter", SecondaryConfirm, Some(INPUT_CONTEXT)),
+        KeyBinding::new("escape", Dismiss, Some(INPUT_CONTEXT)),
+    ]);
+}
+
+/// An item listed by a [`TabPicker`].
+pub(crate) struct TabPickerEntry {
+    pub(crate) item: Box<dyn ItemHandle>,
+    pub(crate) pane: WeakEntity<Pane>,
+    /// Whether the entry is selected until a query is typed.
+    pub(crate) is_active: bool,
+    detail: usize,
+    /// Shown after the tab, such as where its pane is.
+    label: Option<SharedString>,
+    /// The texts matched by the query: the title, description and tooltip of the tab.
+    texts: Vec<SharedString>,
+    is_pinned: bool,
+    is_preview: bool,
+    is_dirty: bool,
+}
+
+impl TabPickerEntry {
+    /// Returns the entries of the items of `pane` in tab order, the active item being active.
+    pub(crate) fn for_pane(
+        pane: &Entity<Pane>,
+        label: Option<SharedString>,
+        cx: &App,
+    ) -> Vec<Self> {
+        let pane_ref = pane.read(cx);
+        let items = pane_ref
+            .items()
+            .map(|item| item.boxed_clone())
+            .collect::<Vec<_>>();
+        let details = pane::tab_details(&items, cx);
+        let active_item_id = pane_ref.active_item().map(|item| item.item_id());
+        items
+            .into_iter()
+            .zip(details)
+            .enumerate()
+            .map(|(ix, (item, detail))| Self {
+                texts: [
+                    item.tab_title(cx),
+                    item.tab_description(detail, cx),
+                    item.tab_tooltip(cx),
+                ]
+                .into_iter()
+                .flatten()
+                .collect(),
+                is_active: Some(item.item_id()) == active_item_id,
+                is_pinned: pane_ref.is_tab_pinned(ix),
+                is_preview: pane_ref.preview_item_id() == Some(item.item_id()),
+                is_dirty: item.is_dirty(cx),
+                pane: pane.downgrade(),
+                label: label.clone(),
+                item,
+                detail,
+            })
+            .collect()
+    }
+
+    /// Activate the item in its pane.
+    pub(crate) fn activate(&self, window: &mut Window, cx: &mut App) {
+        let item_id = self.item.item_id();
+        if let Some(pane) = self.pane.upgrade() {
+            pane.update(cx, |pane, cx| {
+                if let Some(index) = pane.index_for_item_id(item_id) {
+                    pane.activate_item(index, true, true, window, cx);
+                }
+            });
+        }
+    }
+}
+
+/// Called with the chosen entry, and whether it was chosen with [`SecondaryConfirm`].
+type ConfirmHandler = Box<dyn Fn(&TabPickerEntry, bool, &mut Window, &mut App)>;
+
+/// A list of tabs filtered by a fuzzy query, dismissed once an entry is chosen.
+pub(crate) struct TabPicker {
+    entries: Vec<TabPickerEntry>,
+    /// The indices of the entries matching the query, best match first.
+    matches: Vec<usize>,
+    selected_index: usize,
+    query_input: Entity<TextInput>,
+    on_confirm: ConfirmHandler,
+    width: Pixels,
+    max_height: Pixels,
+    _subscription: Subscription,
+}
+
+impl TabPicker {
+    pub(crate) fn new(
+        entries: Vec<TabPickerEntry>,
+        on_confirm: impl Fn(&TabPickerEntry, bool, &mut Window, &mut App) + 'static,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let query_input = cx.new(|cx| TextInput::new(window, cx).placeholder("Search tabs..."));
+        let _subscription = cx.subscribe_in(&query_input, window, Self::on_query_input_event);
+
+        let mut this = Self {
+            entries,
+            matches: Vec::new(),
+            selected_index: 0,
+            query_input,
+            on_confirm: Box::new(on_confirm),
+            width: px(500.),
+            max_height: px(400.),
+            _subscription,
+        };
+        this.update_matches(cx);
+        this
+    }
+
+    pub(crate) fn width(mut self, width: Pixels) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Set the height over which the list scrolls.
+    pub(crate) fn max_height(mut self, max_height: Pixels) -> Self {
+        self.max_height = max_height;
+        self
+    }
+
+    fn on_query_input_event(
+        &mut self,
+        _: &Entity<TextInput>,
+        event: &InputEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let InputEvent::Change(_) = event {
+            self.update_matches(cx);
+        }
+    }
+
+    fn update_matches(&mut self, cx: &mut Context<Self>) {
+        let query = self.query_input.read(cx).text();
+        if query.trim().is_empty() {
+            self.matches = (0..self.entries.len()).collect();
+            // Select the active item until a query is typed.
+            self.selected_index = self
+                .entries
+                .iter()
+                .position(|entry| entry.is_active)
+                .unwrap_or(0);
+            cx.notify();
+            return;
+        }
+
+        let mut matches = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, entry)| {
+                let score = entry
+                    .texts
+                    .iter()
+                    .filter_map(|text| fuzzy_score(&query, text))
+                    .max()?;
+                Some((score, ix))
+            })
+            .collect::<Vec<_>>();
+        // The sort is stable, so equal matches keep the order of the entries.
+        matches.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        self.matches = matches.into_iter().map(|(_, ix)| ix).collect();
+        self.selected_index = 0;
+        cx.notify();
+    }
+
+    fn select_next(&mut self, cx: &mut Context<Self>) {
+        if !self.matches.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.matches.len();
+            cx.notify();
+        }
+    }
+
+    fn select_previous(&mut self, cx: &mut Context<Self>) {
+        if !self.matches.is_empty() {
+            self.selected_index =
+                (self.selected_index + self.matches.len() - 1) % self.matches.len();
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, secondary: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(entry) = self
+            .matches
+            .get(self.selected_index)
+            .map(|ix| &self.entries[*ix])
+        else {
+            return;
+        };
+
+        (self.on_confirm)(entry, secondary, window, cx);
+        cx.emit(DismissEvent);
+    }
+}
+
+/// Returns the score of `text` if it contains the characters of `query` in order, ignoring
+/// case and whitespace in the query. Consecutive characters and characters at the start of
+/// a word score higher.
+fn fuzzy_score(query: &str, text: &str) -> Option<usize> {
+    let text = text.chars().collect::<Vec<_>>();
+    let mut score = 0;
+    let mut start_ix = 0;
+    let mut previous_ix = None;
+    for query_char in query.chars().filter(|c| !c.is_whitespace()) {
+        let ix = (start_ix..text.len())
+            .find(|ix| text[*ix].to_lowercase().eq(query_char.to_lowercase()))?;
+        score += 1;
+        if ix > 0 && previous_ix == Some(ix - 1) {
+            score += 3;
+        }
+        if ix == 0 || !text[ix - 1].is_alphanumeric() {
+            score += 2;
+        }
+        previous_ix = Some(ix);
+        start_ix = ix + 1;
+    }
+    Some(score)
+}
+
+impl EventEmitter<DismissEvent> for TabPicker {}
+
+impl Focusable for TabPicker {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.query_input.focus_handle(cx)
+    }
+}
+
+impl Render for TabPicker {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context(CONTEXT)
+            .w(self.width)
+            .gap_1()
+            .on_action(cx.listener(|this, _: &SelectNext, _, cx| this.select_next(cx)))
+            .on_action(cx.listener(|this, _: &SelectPrevious, _, cx| this.select_previous(cx)))
+            .on_action(cx.listener(|this, _: &Confirm, window, cx| this.confirm(false, window, cx)))
+            .on_action(
+                cx.listener(|this, _: &SecondaryConfirm, window, cx| {
+                    this.confirm(true, window, cx)
+                }),
+            )
+            .on_action(cx.listener(|_, _: &Dismiss, _, cx| cx.emit(DismissEvent)))
+            .child(self.query_input.clone())
+            .when(self.matches.is_empty(), |this| {
+                this.child(
+                    h_flex()
+                        .px_2()
+                        .py_1()
+                        .child(Label::new("No matching tabs.")),
+                )
+            })
+            .child(
+                v_flex()
+                    .id("tab-picker-entries")
+                    .max_h(self.max_height)
+                    .overflow_y_scroll()
+                    .children(self.matches.iter().enumerate().map(|(ix, entry_ix)| {
+                        let entry = &self.entries[*entry_ix];
+                        let selected = ix == self.selected_index;
+                        h_flex()
+                            .id(ix)
+                            .w_full()
+                            .gap_2()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .when(selected, |this| this.bg(cx.theme().accent))
+                            .child(render_tab_icon(entry.item.colored_tab_icon(window, cx)))
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .overflow_hidden()
+                                    .child(entry.item.tab_content(
+                                        TabContentParams {
+                                            detail: Some(entry.detail),
+                                            selected: entry.is_active,
+                                            is_pinned: entry.is_pinned,
+                                            preview: entry.is_preview,
+                                        },
+                                        window,
+                                        cx,
+                                    )),
+                            )
+                            .when(entry.is_pinned, |this| {
+                                this.child(
+                                    Label::new("Pinned").text_color(cx.theme().muted_foreground),
+                                )
+                            })
+                            .when_some(entry.label.clone(), |this, label| {
+                                this.child(
+                                    Label::new(label).text_color(cx.theme().muted_foreground),
+                                )
+                            })
+                            .when(entry.is_dirty, |this| {
+                                this.child(
+                                    div()
+                                        .flex_none()
+                                        .size(px(6.))
+                                        .rounded_full()
+                                        .bg(cx.theme().foreground),
+                                )
+                            })
+                            .on_click(cx.listener(move |this, _, window, cx| {
+                                this.selected_index = ix;
+                                this.confirm(false, window, cx);
+                            }))
+                    })),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert_eq!(fuzzy_score("", "main.rs"), Some(0));
+        assert_eq!(fuzzy_score("abc", "abc"), Some(11));
+        assert_eq!(fuzzy_score("ABC", "abc"), Some(11));
+        assert_eq!(fuzzy_score("a b c", "abc"), Some(11));
+        assert_eq!(fuzzy_score("mr", "main.rs"), Some(6));
+
+        assert_eq!(fuzzy_score("xyz", "main.rs"), None);
+        assert_eq!(fuzzy_score("ba", "ab"), None);
+        assert_eq!(fuzzy_score("mainn", "main.rs"), None);
+    }
+
+    #[test]
+    fn test_fuzzy_score_ranking() {
+        // Consecutive characters score higher than scattered ones.
+        assert!(fuzzy_score("ma", "main") > fuzzy_score("ma", "mxa"));
+        // Characters at the start of a word score higher than inside a word.
+        assert!(fuzzy_score("r", "main.rs") > fuzzy_score("r", "error"));
+    }
+}
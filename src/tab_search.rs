@@ -0,0 +1,163 @@
+//! A palette to fuzzy search the items of every pane of the workspace, including the docks.
+
+use gpui::{
+    actions, prelude::FluentBuilder as _, App, AppContext as _, Context, DismissEvent, Entity,
+    EventEmitter, FocusHandle, Focusable, IntoElement, KeyBinding, ParentElement as _, Render,
+    Styled as _, Subscription, WeakEntity, Window,
+};
+use ui::{h_flex, label::Label, theme::ActiveTheme, v_flex};
+
+use crate::{
+    modal_layer::ModalView,
+    pane_group::SplitDirection,
+    tab_picker::{SecondaryConfirm, TabPicker, TabPickerEntry},
+    Workspace,
+};
+
+actions!(tab_search, [Toggle]);
+
+/// Bind the default key of the tab search, the keys of its list are bound by
+/// [`tab_picker::init`].
+///
+/// [`tab_picker::init`]: crate::tab_picker::init
+pub fn init(cx: &mut App) {
+    cx.bind_keys([KeyBinding::new("secondary-p", Toggle, Some("Workspace"))]);
+}
+
+pub struct TabSearch {
+    picker: Entity<TabPicker>,
+    _subscription: Subscription,
+}
+
+impl TabSearch {
+    pub fn toggle(
+        workspace: &mut Workspace,
+        _: &Toggle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        let active_pane = workspace.active_pane().clone();
+        let mut entries = Vec::new();
+        for (pane, pane_label) in workspace.labeled_panes(cx) {
+            let is_active_pane = pane == active_pane;
+            entries.extend(
+                TabPickerEntry::for_pane(&pane, Some(pane_label), cx)
+                    .into_iter()
+                    .map(|mut entry| {
+                        entry.is_active &= is_active_pane;
+                        entry
+                    }),
+            );
+        }
+
+        let weak_workspace = workspace.weak_handle();
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(weak_workspace, entries, window, cx)
+        });
+    }
+
+    fn new(
+        workspace: WeakEntity<Workspace>,
+        entries: Vec<TabPickerEntry>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let picker = cx.new(|cx| {
+            TabPicker::new(
+                entries,
+                move |entry, split, window, cx| confirm(&workspace, entry, split, window, cx),
+                window,
+                cx,
+            )
+        });
+        let _subscription = cx.subscribe(&picker, |_, _, _: &DismissEvent, cx| {
+            cx.emit(DismissEvent);
+        });
+
+        Self {
+            picker,
+            _subscription,
+        }
+    }
+}
+
+/// Activate the chosen item in its pane, or move it to a new split on the right of the
+/// active pane when `split` is set.
+fn confirm(
+    workspace: &WeakEntity<Workspace>,
+    entry: &TabPickerEntry,
+    split: bool,
+    window: &mut Window,
+    cx: &mut App,
+) {
+    let (Some(workspace), Some(pane)) = (workspace.upgrade(), entry.pane.upgrade()) else {
+        return;
+    };
+
+    let item_id = entry.item.item_id();
+    workspace.update(cx, |workspace, cx| {
+        let pane_to_split = workspace.active_pane().clone();
+        // Only the center panes can be split, and splitting a pane with its only item
+        // would give the same layout.
+        let can_split = workspace.panes().contains(&pane_to_split)
+            && !(pane == pane_to_split && pane.read(cx).items_len() == 1);
+        if split && can_split {
+            workspace.split_pane_with_item(
+                pane_to_split.downgrade(),
+                SplitDirection::Right,
+                pane.downgrade(),
+                item_id,
+                window,
+                cx,
+            );
+        } else {
+            workspace.reveal_pane(&pane, window, cx);
+            entry.activate(window, cx);
+        }
+    });
+}
+
+impl EventEmitter<DismissEvent> for TabSearch {}
+
+impl Focusable for TabSearch {
+    fn focus_handle(&self, cx: &App) -> FocusHandle {
+        self.picker.focus_handle(cx)
+    }
+}
+
+impl ModalView for TabSearch {}
+
+impl Render for TabSearch {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let split_keystrokes =
+            window
+                .bindings_for_action(&SecondaryConfirm)
+                .last()
+                .map(|binding| {
+                    binding
+                        .keystrokes()
+                        .iter()
+                        .map(|keystroke| keystroke.to_string())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                });
+
+        v_flex()
+            .p_1()
+            .gap_1()
+            .bg(cx.theme().background)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded_md()
+            .shadow_lg()
+            .child(self.picker.clone())
+            .when_some(split_keystrokes, |this, keystrokes| {
+                this.child(
+                    h_flex().px_2().py_1().child(
+                        Label::new(format!("{} to open in a split", keystrokes))
+                            .text_color(cx.theme().muted_foreground),
+                    ),
+                )
+            })
+    }
+}
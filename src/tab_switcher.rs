@@ -0,0 +1,279 @@
+//! A Ctrl+Tab style switcher listing the items by most recent use.
+//!
+//! The switcher opens with the previously active item selected, cycles while the keys
+//! that opened it are held, and activates the selected item once they are released.
+
+use gpui::{
+    actions, impl_actions, prelude::FluentBuilder as _, px, App, Context, DismissEvent,
+    EventEmitter, FocusHandle, Focusable, InteractiveElement as _, IntoElement, KeyBinding,
+    Modifiers, ModifiersChangedEvent, ParentElement as _, Render, StatefulInteractiveElement as _,
+    Styled as _, WeakEntity, Window,
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use ui::{h_flex, label::Label, theme::ActiveTheme, v_flex};
+
+use crate::{
+    item::{ItemHandle, TabContentParams},
+    modal_layer::ModalView,
+    pane::{self, render_tab_icon, Pane},
+    Workspace,
+};
+
+const CONTEXT: &str = "TabSwitcher";
+
+/// Open the tab switcher for the active pane, or select the next item if it is open.
+#[derive(Clone, Default, Deserialize, JsonSchema, PartialEq)]
+pub struct Toggle {
+    /// Select the least recently used item rather than the next one.
+    #[serde(default)]
+    pub select_last: bool,
+}
+
+impl_actions!(tab_switcher, [Toggle]);
+
+actions!(
+    tab_switcher,
+    [ToggleAll, SelectNext, SelectPrevious, Confirm, Dismiss]
+);
+
+/// Bind the default keys of the tab switcher.
+pub fn init(cx: &mut App) {
+    cx.bind_keys([
+        KeyBinding::new("ctrl-tab", Toggle::default(), Some("Workspace")),
+        KeyBinding::new(
+            "ctrl-shift-tab",
+            Toggle { select_last: true },
+            Some("Workspace"),
+        ),
+        KeyBinding::new("ctrl-alt-tab", ToggleAll, Some("Workspace")),
+        KeyBinding::new("down", SelectNext, Some(CONTEXT)),
+        KeyBinding::new("up", SelectPrevious, Some(CONTEXT)),
+        KeyBinding::new("enter", Confirm, Some(CONTEXT)),
+        KeyBinding::new("escape", Dismiss, Some(CONTEXT)),
+    ]);
+}
+
+struct TabSwitcherEntry {
+    item: Box<dyn ItemHandle>,
+    pane: WeakEntity<Pane>,
+    detail: usize,
+    is_pinned: bool,
+    is_preview: bool,
+}
+
+pub struct TabSwitcher {
+    entries: Vec<TabSwitcherEntry>,
+    selected_index: usize,
+    /// The modifiers held when the switcher was opened, releasing them confirms the selection.
+    init_modifiers: Option<Modifiers>,
+    focus_handle: FocusHandle,
+}
+
+impl TabSwitcher {
+    pub fn toggle(
+        workspace: &mut Workspace,
+        action: &Toggle,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        Self::open(workspace, action.select_last, false, window, cx);
+    }
+
+    /// Open the tab switcher for the items of every pane.
+    pub fn toggle_all(
+        workspace: &mut Workspace,
+        _: &ToggleAll,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        Self::open(workspace, false, true, window, cx);
+    }
+
+    fn open(
+        workspace: &mut Workspace,
+        select_last: bool,
+        all_panes: bool,
+        window: &mut Window,
+        cx: &mut Context<Workspace>,
+    ) {
+        if let Some(tab_switcher) = workspace.active_modal::<Self>(cx) {
+            tab_switcher.update(cx, |tab_switcher, cx| {
+                if select_last {
+                    tab_switcher.select_previous(cx);
+                } else {
+                    tab_switcher.select_next(cx);
+                }
+            });
+            return;
+        }
+
+        let panes = if all_panes {
+            workspace.panes().to_vec()
+        } else {
+            vec![workspace.active_pane().clone()]
+        };
+
+        let mut entries = Vec::new();
+        for pane in &panes {
+            let pane_ref = pane.read(cx);
+            for (ix, item) in pane_ref.items().enumerate() {
+                let timestamp = pane_ref
+                    .activation_history()
+                    .iter()
+                    .find(|entry| entry.entity_id == item.item_id())
+                    .map(|entry| entry.timestamp);
+                entries.push((
+                    timestamp,
+                    TabSwitcherEntry {
+                        item: item.boxed_clone(),
+                        pane: pane.downgrade(),
+                        detail: 0,
+                        is_pinned: pane_ref.is_tab_pinned(ix),
+                        is_preview: pane_ref.preview_item_id() == Some(item.item_id()),
+                    },
+                ));
+            }
+        }
+        // Most recently used first, items that were never activated last.
+        entries.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let items = entries
+            .iter()
+            .map(|(_, entry)| entry.item.boxed_clone())
+            .collect::<Vec<_>>();
+        let details = pane::tab_details(&items, cx);
+        let entries = entries
+            .into_iter()
+            .zip(details)
+            .map(|((_, entry), detail)| TabSwitcherEntry { detail, ..entry })
+            .collect();
+
+        workspace.toggle_modal(window, cx, |window, cx| {
+            Self::new(entries, select_last, window, cx)
+        });
+    }
+
+    fn new(
+        entries: Vec<TabSwitcherEntry>,
+        select_last: bool,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let selected_index = if select_last {
+            entries.len().saturating_sub(1)
+        } else {
+            entries.len().min(1)
+        };
+        let modifiers = window.modifiers();
+
+        Self {
+            entries,
+            selected_index,
+            init_modifiers: modifiers.modified().then_some(modifiers),
+            focus_handle: cx.focus_handle(),
+        }
+    }
+
+    fn select_next(&mut self, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.selected_index = (self.selected_index + 1) % self.entries.len();
+            cx.notify();
+        }
+    }
+
+    fn select_previous(&mut self, cx: &mut Context<Self>) {
+        if !self.entries.is_empty() {
+            self.selected_index =
+                (self.selected_index + self.entries.len() - 1) % self.entries.len();
+            cx.notify();
+        }
+    }
+
+    fn confirm(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(entry) = self.entries.get(self.selected_index) {
+            let item_id = entry.item.item_id();
+            if let Some(pane) = entry.pane.upgrade() {
+                pane.update(cx, |pane, cx| {
+                    if let Some(index) = pane.index_for_item_id(item_id) {
+                        pane.activate_item(index, true, true, window, cx);
+                    }
+                });
+            }
+        }
+        cx.emit(DismissEvent);
+    }
+
+    fn handle_modifiers_changed(
+        &mut self,
+        event: &ModifiersChangedEvent,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.init_modifiers.is_none() || event.modifiers.modified() {
+            return;
+        }
+
+        self.init_modifiers = None;
+        self.confirm(window, cx);
+    }
+}
+
+impl EventEmitter<DismissEvent> for TabSwitcher {}
+
+impl Focusable for TabSwitcher {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl ModalView for TabSwitcher {}
+
+impl Render for TabSwitcher {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        v_flex()
+            .key_context(CONTEXT)
+            .track_focus(&self.focus_handle)
+            .w(px(400.))
+            .p_1()
+            .bg(cx.theme().background)
+            .border_1()
+            .border_color(cx.theme().border)
+            .rounded_md()
+            .shadow_lg()
+            .on_modifiers_changed(cx.listener(Self::handle_modifiers_changed))
+            .on_action(cx.listener(|this, _: &SelectNext, _, cx| this.select_next(cx)))
+            .on_action(cx.listener(|this, _: &SelectPrevious, _, cx| this.select_previous(cx)))
+            .on_action(cx.listener(|this, _: &Confirm, window, cx| this.confirm(window, cx)))
+            .on_action(cx.listener(|_, _: &Dismiss, _, cx| cx.emit(DismissEvent)))
+            .when(self.entries.is_empty(), |this| {
+                this.child(h_flex().px_2().py_1().child(Label::new("No open tabs.")))
+            })
+            .children(self.entries.iter().enumerate().map(|(ix, entry)| {
+                let selected = ix == self.selected_index;
+                h_flex()
+                    .id(ix)
+                    .w_full()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .gap_2()
+                    .when(selected, |this| this.bg(cx.theme().accent))
+                    .child(render_tab_icon(entry.item.colored_tab_icon(window, cx)))
+                    .child(entry.item.tab_content(
+                        TabContentParams {
+                            detail: Some(entry.detail),
+                            selected,
+                            is_pinned: entry.is_pinned,
+                            preview: entry.is_preview,
+                        },
+                        window,
+                        cx,
+                    ))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.selected_index = ix;
+                        this.confirm(window, cx);
+                    }))
+            }))
+    }
+}
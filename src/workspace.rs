@@ -1,27 +1,45 @@
 use std::{
     cmp,
-    collections::{hash_map, HashMap},
-    sync::{atomic::AtomicUsize, Arc},
+    collections::{hash_map, HashMap, VecDeque},
+    mem,
+    num::NonZeroUsize,
+    rc::Rc,
+    sync::Arc,
     time::Duration,
 };
 
 use crate::{
     dock::{Panel, PanelHandle},
-    pane_group,
+    item::{ItemHandle, WeakItemHandle},
+    layout::{LayoutError, LayoutSpec},
+    modal_layer::{ModalLayer, ModalView},
+    pane_group::{self, Member},
+    persistence::{
+        self,
+        model::{
+            DockStructure, ItemId, RestoreItem, SerializedItem, SerializedPane,
+            SerializedPaneGroup, SerializedWorkspace,
+        },
+    },
+    tab_search::TabSearch,
+    tab_switcher::TabSwitcher,
+    util::ResultExt,
 };
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use gpui::{
-    actions, canvas, div, impl_internal_actions, prelude::FluentBuilder as _, AnyWeakView, App,
-    AppContext, Bounds, Context, Div, DragMoveEvent, Entity, EntityId, EventEmitter, FocusHandle,
-    Focusable, InteractiveElement as _, IntoElement, KeyContext, ParentElement as _, Pixels, Point,
-    Render, Styled as _, Subscription, Task, WeakEntity, Window,
+    actions, canvas, div, impl_internal_actions, point, prelude::FluentBuilder as _, px,
+    AnyWeakView, AnyWindowHandle, App, AppContext, Bounds, Context, Div, DragMoveEvent, Entity,
+    EntityId, EventEmitter, FocusHandle, Focusable, Global, InteractiveElement as _, IntoElement,
+    KeyContext, MouseButton, MouseUpEvent, ParentElement as _, Pixels, Point, Render, SharedString,
+    Styled as _, Subscription, Task, WeakEntity, Window, WindowBounds, WindowOptions,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use ui::{h_flex, theme::ActiveTheme};
 
 use super::{
     dock::{Dock, DockPosition},
-    pane::{self, Pane},
+    item,
+    pane::{self, CloseOutcome, DraggedTab, NavTimeline, NavigationEntry, NavigationMode, Pane},
     pane_group::{PaneGroup, SplitDirection},
 };
 
@@ -39,6 +57,13 @@ actions!(
         CloseAllItemsAndPanes,
         CloseInactiveTabsAndPanes,
         ReopenClosedItem,
+        ActivateNextLayoutPreset,
+        ActivatePreviousLayoutPreset,
+        MoveItemLeft,
+        MoveItemRight,
+        MoveItemToStart,
+        MoveItemToEnd,
+        MoveItemToNewWindow,
     ]
 );
 
@@ -51,14 +76,78 @@ pub struct ActivatePaneInDirection(pub SplitDirection);
 #[derive(Clone, Deserialize, PartialEq)]
 pub struct SwapPaneInDirection(pub SplitDirection);
 
+/// Move the active item to the center pane at the given index, or to a new pane on the right
+/// if there is no pane at that index.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct MoveItemToPane(pub usize);
+
+/// Move the active item to the pane in the given direction, or to a new split if there is no
+/// pane in that direction.
+#[derive(Clone, Deserialize, PartialEq)]
+pub struct MoveItemToPaneInDirection(pub SplitDirection);
+
 impl_internal_actions!(
     workspace,
-    [ActivatePane, ActivatePaneInDirection, SwapPaneInDirection,]
+    [
+        ActivatePane,
+        ActivatePaneInDirection,
+        SwapPaneInDirection,
+        MoveItemToPane,
+        MoveItemToPaneInDirection,
+    ]
 );
 
-#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize,
+)]
 pub struct WorkspaceId(i64);
 
+impl From<i64> for WorkspaceId {
+    fn from(id: i64) -> Self {
+        Self(id)
+    }
+}
+
+impl From<WorkspaceId> for i64 {
+    fn from(id: WorkspaceId) -> Self {
+        id.0
+    }
+}
+
+const MAX_CLOSED_ITEMS: usize = 32;
+
+/// Opens the window of a new workspace with the given options, see
+/// [`Workspace::set_new_window_builder`].
+type NewWindowBuilder = Rc<dyn Fn(WindowOptions, &mut App) -> Result<Entity<Workspace>>>;
+
+struct GlobalNewWindowBuilder(NewWindowBuilder);
+
+impl Global for GlobalNewWindowBuilder {}
+
+/// Every workspace of the app, to find the one under a tab dropped outside of its window.
+#[derive(Default)]
+struct WorkspaceRegistry(Vec<WeakEntity<Workspace>>);
+
+impl Global for WorkspaceRegistry {}
+
+/// An item closed by the user, see [`Workspace::reopen_closed_item`].
+struct ClosedItem {
+    item: Box<dyn WeakItemHandle>,
+    /// The state to recreate the item with once it was released, if it is a [`SerializableItem`].
+    ///
+    /// [`SerializableItem`]: crate::item::SerializableItem
+    serialized_item: Option<SerializedItem>,
+    pane: WeakEntity<Pane>,
+    index: usize,
+    is_pinned: bool,
+}
+
+/// A named snapshot of the layout, see [`Workspace::save_layout_preset`].
+struct LayoutPreset {
+    name: SharedString,
+    layout: SerializedWorkspace,
+}
+
 enum ActivateInDirectionTarget {
     Pane(Entity<Pane>),
     Dock(Entity<Dock>),
@@ -74,6 +163,8 @@ pub struct Workspace {
     right_dock: Entity<Dock>,
     panes: Vec<Entity<Pane>>,
     pub(crate) panes_by_item: HashMap<EntityId, WeakEntity<Pane>>,
+    /// The subscriptions to the events of the center panes, dropped with their pane.
+    pane_subscriptions: HashMap<EntityId, Subscription>,
     active_pane: Entity<Pane>,
     last_active_center_pane: Option<WeakEntity<Pane>>,
     pub(crate) zoomed: Option<AnyWeakView>,
@@ -82,6 +173,17 @@ pub struct Workspace {
     bounds: Bounds<Pixels>,
     workspace_actions: Vec<Box<dyn Fn(Div, &mut Window, &mut Context<Self>) -> Div>>,
     bounds_save_task_queued: Option<Task<()>>,
+    serialize_task_queued: Option<Task<()>>,
+    layout_presets: Vec<LayoutPreset>,
+    active_layout_preset: Option<usize>,
+    nav_timeline: Arc<NavTimeline>,
+    closed_items: VecDeque<ClosedItem>,
+    modal_layer: Entity<ModalLayer>,
+    max_tabs: Option<NonZeroUsize>,
+    window_handle: AnyWindowHandle,
+    /// The tab being dragged while the cursor is outside of the window, to move it to the
+    /// window under the cursor, or tear it off into a new window, when it is dropped there.
+    torn_off_tab: Option<DraggedTab>,
     _subscriptions: Vec<Subscription>,
 }
 
@@ -93,13 +195,14 @@ pub enum Event {
     ActiveItemChanged,
     WorkspaceCreated(WeakEntity<Workspace>),
     ZoomChanged,
+    WorkspaceSerialized(Box<SerializedWorkspace>),
 }
 
 impl EventEmitter<Event> for Workspace {}
 
 impl Focusable for Workspace {
     fn focus_handle(&self, cx: &App) -> FocusHandle {
-        self.active_pane.focus_handle(cx)
+        self.active_center_pane().focus_handle(cx)
     }
 }
 
@@ -152,6 +255,41 @@ impl Render for Workspace {
                         .absolute()
                         .size_full()
                     })
+                    .on_drag_move(
+                        cx.listener(|workspace, e: &DragMoveEvent<DraggedTab>, _, cx| {
+                            workspace.torn_off_tab =
+                                (!e.bounds.contains(&e.event.position)).then(|| e.drag(cx).clone());
+                        }),
+                    )
+                    .on_mouse_up_out(
+                        MouseButton::Left,
+                        cx.listener(|workspace, e: &MouseUpEvent, window, cx| {
+                            if let Some(dragged_tab) = workspace.torn_off_tab.take() {
+                                workspace
+                                    .drop_tab_outside_of_window(
+                                        &dragged_tab,
+                                        e.position,
+                                        window,
+                                        cx,
+                                    )
+                                    .log_err();
+                            }
+                        }),
+                    )
+                    // The panes handle the drops over them first, any other drop tears the tab off.
+                    .on_drop(
+                        cx.listener(|workspace, dragged_tab: &DraggedTab, window, cx| {
+                            workspace
+                                .move_item_to_new_window(
+                                    dragged_tab.pane.clone(),
+                                    dragged_tab.item.item_id(),
+                                    Some(window.mouse_position()),
+                                    window,
+                                    cx,
+                                )
+                                .log_err();
+                        }),
+                    )
                     .when(self.zoomed.is_none(), |this| {
                         this.on_drag_move(cx.listener(
                             |workspace, e: &DragMoveEvent<DraggedDock>, window, cx| {
@@ -241,7 +379,8 @@ impl Render for Workspace {
                             Some(DockPosition::Bottom) => div.top_2().border_t_1(),
                             None => div.top_2().bottom_2().left_2().right_2().border_1(),
                         })
-                    })),
+                    }))
+                    .child(self.modal_layer.clone()),
             )
     }
 }
@@ -259,19 +398,26 @@ impl Workspace {
         .detach();
 
         let weak_handle = cx.entity().downgrade();
-        let _pane_history_timestamp = Arc::new(AtomicUsize::new(0));
-
-        let center_pane = cx.new(|cx| Pane::new(weak_handle.clone(), None, window, cx));
-        cx.subscribe_in(&center_pane, window, Self::handle_pane_event)
-            .detach();
+        let nav_timeline = Arc::new(NavTimeline::default());
+
+        let center_pane =
+            cx.new(|cx| Pane::new(weak_handle.clone(), nav_timeline.clone(), None, window, cx));
+        let pane_subscriptions = HashMap::from_iter([(
+            center_pane.entity_id(),
+            cx.subscribe_in(&center_pane, window, Self::handle_pane_event),
+        )]);
         cx.focus_view(&center_pane, window);
         cx.emit(Event::PaneAdded(center_pane.clone()));
         // let window_handle = cx.window_handle().downcast::<Workspace>().unwrap();
 
         cx.emit(Event::WorkspaceCreated(weak_handle.clone()));
+        let registry = cx.default_global::<WorkspaceRegistry>();
+        registry.0.retain(|workspace| workspace.upgrade().is_some());
+        registry.0.push(weak_handle.clone());
         let left_dock = Dock::new(DockPosition::Left, window, cx);
         let bottom_dock = Dock::new(DockPosition::Bottom, window, cx);
         let right_dock = Dock::new(DockPosition::Right, window, cx);
+        let modal_layer = cx.new(|_| ModalLayer::new());
         // let left_dock_buttons = cx.new_view(|cx| PanelButtons::new(left_dock.clone(), cx));
         // let bottom_dock_buttons = cx.new_view(|cx| PanelButtons::new(bottom_dock.clone(), cx));
         // let right_dock_buttons = cx.new_view(|cx| PanelButtons::new(right_dock.clone(), cx));
@@ -289,16 +435,20 @@ impl Workspace {
                             .await;
                         this.update_in(&mut cx, |this, window, cx| {
                             if let Some(display) = window.display(cx) {
-                                if let Ok(_display_uuid) = display.uuid() {
-                                    let _window_bounds = window.window_bounds();
-                                    if let Some(_database_id) = workspace_id {
-                                        // cx.background_executor()
-                                        //     .spawn(DB.set_window_open_status(
-                                        //         database_id,
-                                        //         SerializedWindowBounds(window_bounds),
-                                        //         display_uuid,
-                                        //     ))
-                                        //     .detach_and_log_err(cx);
+                                if let Ok(display_uuid) = display.uuid() {
+                                    let window_bounds = window.window_bounds();
+                                    if let Some((database_id, persistence)) =
+                                        workspace_id.zip(persistence::persistence(cx))
+                                    {
+                                        cx.background_executor()
+                                            .spawn(async move {
+                                                persistence.save_window_bounds(
+                                                    database_id,
+                                                    display_uuid.to_string(),
+                                                    window_bounds.into(),
+                                                )
+                                            })
+                                            .detach_and_log_err(cx);
                                     }
                                 }
                             }
@@ -335,6 +485,7 @@ impl Workspace {
             center: PaneGroup::new(center_pane.clone()),
             panes: vec![center_pane.clone()],
             panes_by_item: Default::default(),
+            pane_subscriptions,
             active_pane: center_pane.clone(),
             last_active_center_pane: Some(center_pane.downgrade()),
             left_dock,
@@ -345,16 +496,43 @@ impl Workspace {
             // This data will be incorrect, but it will be overwritten by the time it needs to be used.
             bounds: Default::default(),
             bounds_save_task_queued: None,
+            serialize_task_queued: None,
+            layout_presets: Vec::new(),
+            active_layout_preset: None,
+            nav_timeline,
+            closed_items: VecDeque::new(),
+            modal_layer,
+            max_tabs: None,
+            window_handle: window.window_handle(),
+            torn_off_tab: None,
             _subscriptions: subscriptions,
         }
     }
 
+    /// Returns the window options to reopen the window of the workspace where the user left it.
+    ///
+    /// Only the bounds and the display are set, the other options are left to their defaults.
+    pub fn window_options(workspace_id: WorkspaceId, cx: &App) -> WindowOptions {
+        let (window_bounds, display_id) = persistence::restore_window_bounds(workspace_id, cx)
+            .map_or((None, None), |(bounds, display_id)| {
+                (Some(bounds), display_id)
+            });
+
+        WindowOptions {
+            window_bounds,
+            display_id,
+            ..Default::default()
+        }
+    }
+
     pub fn on_window_activation_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         if window.is_window_active() {
-            if let Some(_database_id) = self.database_id {
-                // cx.background_executor()
-                //     .spawn(persistence::DB.update_timestamp(database_id))
-                //     .detach();
+            if let Some((database_id, persistence)) =
+                self.database_id.zip(persistence::persistence(cx))
+            {
+                cx.background_executor()
+                    .spawn(async move { persistence.update_timestamp(database_id) })
+                    .detach_and_log_err(cx);
             }
         } else {
             for pane in &self.panes {
@@ -433,9 +611,46 @@ impl Workspace {
                 }),
             )
             .on_action(cx.listener(Workspace::activate_pane_at_index))
+            .on_action(cx.listener(|workspace, _: &MoveItemLeft, window, cx| {
+                workspace.move_active_item(|ix, _| ix.saturating_sub(1), window, cx)
+            }))
+            .on_action(cx.listener(|workspace, _: &MoveItemRight, window, cx| {
+                workspace.move_active_item(|ix, _| ix + 1, window, cx)
+            }))
+            .on_action(cx.listener(|workspace, _: &MoveItemToStart, window, cx| {
+                workspace.move_active_item(|_, _| 0, window, cx)
+            }))
+            .on_action(cx.listener(|workspace, _: &MoveItemToEnd, window, cx| {
+                workspace.move_active_item(|_, len| len, window, cx)
+            }))
+            .on_action(
+                cx.listener(|workspace, action: &MoveItemToPane, window, cx| {
+                    workspace.move_item_to_pane_at_index(action.0, window, cx)
+                }),
+            )
+            .on_action(cx.listener(
+                |workspace, action: &MoveItemToPaneInDirection, window, cx| {
+                    workspace.move_item_to_pane_in_direction(action.0, window, cx)
+                },
+            ))
+            .on_action(cx.listener(TabSwitcher::toggle))
+            .on_action(cx.listener(TabSwitcher::toggle_all))
+            .on_action(cx.listener(TabSearch::toggle))
+            .on_action(
+                cx.listener(|workspace, _: &ActivateNextLayoutPreset, window, cx| {
+                    workspace.activate_next_layout_preset(window, cx)
+                }),
+            )
+            .on_action(
+                cx.listener(|workspace, _: &ActivatePreviousLayoutPreset, window, cx| {
+                    workspace.activate_previous_layout_preset(window, cx)
+                }),
+            )
             .on_action(cx.listener(
-                |_workspace: &mut Workspace, _: &ReopenClosedItem, _window, _cx| {
-                    // workspace.reopen_closed_item(cx).detach();
+                |workspace: &mut Workspace, _: &ReopenClosedItem, window, cx| {
+                    workspace
+                        .reopen_closed_item(window, cx)
+                        .detach_and_log_err(cx);
                 },
             ))
     }
@@ -458,7 +673,7 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if let Some(task) = self.close_all_internal(true, window, cx) {
+        if let Some(task) = self.close_all(true, window, cx) {
             task.detach_and_log_err(cx)
         }
     }
@@ -469,51 +684,56 @@ impl Workspace {
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if let Some(task) = self.close_all_internal(false, window, cx) {
+        if let Some(task) = self.close_all(false, window, cx) {
             task.detach_and_log_err(cx)
         }
     }
 
-    fn close_all_internal(
+    /// Close the items of every center pane, keeping the active item of the active pane if
+    /// `retain_active_pane` is set. The outcome reports how many items were closed and whether
+    /// closing stopped at a cancelled save prompt, leaving the remaining items open. Returns
+    /// `None` when there is nothing to close.
+    pub fn close_all(
         &mut self,
         retain_active_pane: bool,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) -> Option<Task<Result<()>>> {
-        let current_pane = self.active_pane();
-
-        let mut tasks = Vec::new();
-
-        if retain_active_pane {
-            if let Some(current_pane_close) = current_pane.update(cx, |pane, cx| {
-                pane.close_inactive_items(&pane::CloseInactiveItems, window, cx)
-            }) {
-                tasks.push(current_pane_close);
-            };
-        }
-
-        for pane in self.panes() {
-            if retain_active_pane && pane.entity_id() == current_pane.entity_id() {
-                continue;
-            }
-
-            if let Some(close_pane_items) = pane.update(cx, |pane: &mut Pane, cx| {
-                pane.close_all_items(&pane::CloseAllItems, window, cx)
-            }) {
-                tasks.push(close_pane_items)
-            }
+    ) -> Option<Task<Result<CloseOutcome>>> {
+        let current_pane = self.active_pane().clone();
+        let panes = self
+            .panes()
+            .iter()
+            .filter(|pane| pane.read(cx).items_len() > 0)
+            .cloned()
+            .collect::<Vec<_>>();
+        if panes.is_empty() {
+            return None;
         }
 
-        if tasks.is_empty() {
-            None
-        } else {
-            Some(cx.spawn(|_, _| async move {
-                for task in tasks {
-                    task.await?
+        // Close the panes one after the other, so that the user answers one save prompt at
+        // a time, and stop at the first pane where closing is cancelled.
+        Some(cx.spawn_in(window, |_, mut cx| async move {
+            let mut outcome = CloseOutcome::default();
+            for pane in panes {
+                let retain_active_item = retain_active_pane && pane == current_pane;
+                let task = pane.update_in(&mut cx, |pane, window, cx| {
+                    if retain_active_item {
+                        pane.close_inactive_items(&pane::CloseInactiveItems, window, cx)
+                    } else {
+                        pane.close_all_items(&pane::CloseAllItems, window, cx)
+                    }
+                })?;
+                if let Some(task) = task {
+                    let pane_outcome = task.await?;
+                    outcome.closed += pane_outcome.closed;
+                    if pane_outcome.cancelled {
+                        outcome.cancelled = true;
+                        break;
+                    }
                 }
-                Ok(())
-            }))
-        }
+            }
+            Ok(outcome)
+        }))
     }
 
     pub fn weak_handle(&self) -> WeakEntity<Self> {
@@ -536,16 +756,87 @@ impl Workspace {
         self.database_id
     }
 
-    fn add_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Pane> {
-        let pane = cx.new(|cx| Pane::new(self.weak_handle(), None, window, cx));
-        cx.subscribe_in(&pane, window, Self::handle_pane_event)
-            .detach();
+    pub fn max_tabs(&self) -> Option<NonZeroUsize> {
+        self.max_tabs
+    }
+
+    /// Limit the number of items of every center pane, including the panes created later.
+    ///
+    /// This replaces the limits given to the center panes with [`Pane::set_max_tabs`].
+    pub fn set_max_tabs(
+        &mut self,
+        max_tabs: Option<NonZeroUsize>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.max_tabs = max_tabs;
+        for pane in &self.panes {
+            pane.update(cx, |pane, cx| pane.set_max_tabs(max_tabs, window, cx));
+        }
+    }
+
+    /// Show the modal built by `build`, or hide it if a modal of the same type is shown.
+    pub fn toggle_modal<V: ModalView, B>(&mut self, window: &mut Window, cx: &mut App, build: B)
+    where
+        B: FnOnce(&mut Window, &mut Context<V>) -> V,
+    {
+        self.modal_layer.update(cx, |modal_layer, cx| {
+            modal_layer.toggle_modal(window, cx, build)
+        })
+    }
+
+    pub fn active_modal<V: ModalView + 'static>(&self, cx: &App) -> Option<Entity<V>> {
+        self.modal_layer.read(cx).active_modal()
+    }
+
+    pub fn hide_modal(&mut self, window: &mut Window, cx: &mut App) -> bool {
+        self.modal_layer
+            .update(cx, |modal_layer, cx| modal_layer.hide_modal(window, cx))
+    }
+
+    pub(crate) fn add_pane(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<Pane> {
+        let pane = cx.new(|cx| {
+            Pane::new(
+                self.weak_handle(),
+                self.nav_timeline.clone(),
+                None,
+                window,
+                cx,
+            )
+        });
+        let max_tabs = self.max_tabs;
+        pane.update(cx, |pane, cx| pane.set_max_tabs(max_tabs, window, cx));
+        self.pane_subscriptions.insert(
+            pane.entity_id(),
+            cx.subscribe_in(&pane, window, Self::handle_pane_event),
+        );
         self.panes.push(pane.clone());
         cx.focus_view(&pane, window);
         cx.emit(Event::PaneAdded(pane.clone()));
         pane
     }
 
+    /// Create a pane for a panel to show its items in, sharing the navigation timeline of the
+    /// center panes. Return it from [`Panel::pane`] to make its items part of the workspace's
+    /// navigation history. Only center panes can be split.
+    pub fn create_pane_for_panel(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Entity<Pane> {
+        cx.new(|cx| {
+            let mut pane = Pane::new(
+                self.weak_handle(),
+                self.nav_timeline.clone(),
+                None,
+                window,
+                cx,
+            );
+            pane.set_can_split(false, window, cx);
+            pane
+        })
+    }
+
     pub fn split_pane(
         &mut self,
         pane_to_split: Entity<Pane>,
@@ -561,6 +852,49 @@ impl Workspace {
         new_pane
     }
 
+    /// Replace the center panes with the tree described by `spec`, in one step.
+    ///
+    /// The layout is validated before anything is changed. Open items that aren't part of
+    /// the layout are kept in the active pane. Returns the new panes, in depth-first order.
+    pub fn apply_layout(
+        &mut self,
+        spec: impl Into<LayoutSpec>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Vec<Entity<Pane>>, LayoutError> {
+        let spec = spec.into();
+        spec.validate()?;
+
+        // Detach the open items from the old panes first, so an item is never in two panes.
+        let layout_item_ids = spec.item_ids();
+        let mut leftover_items = Vec::new();
+        let old_panes = self.center.panes().into_iter().cloned().collect::<Vec<_>>();
+        for pane in &old_panes {
+            pane.update(cx, |pane, cx| {
+                while let Some(item) = pane.items().next().map(|item| item.boxed_clone()) {
+                    pane.detach_item(0, window, cx);
+                    if !layout_item_ids.contains(&item.item_id()) {
+                        leftover_items.push(item);
+                    }
+                }
+            });
+        }
+
+        let mut panes = Vec::new();
+        let (root, active_pane) = spec.build(&mut panes, self, window, cx);
+        let active_pane = active_pane.unwrap_or_else(|| panes[0].clone());
+        active_pane.update(cx, |pane, cx| {
+            for item in leftover_items {
+                let index = pane.items_len();
+                pane.add_item(item, false, false, Some(index), window, cx);
+            }
+        });
+
+        self.set_center(root, Some(active_pane), window, cx);
+        self.serialize_workspace(window, cx);
+        Ok(panes)
+    }
+
     pub fn split_and_clone(
         &mut self,
         pane: Entity<Pane>,
@@ -601,20 +935,22 @@ impl Workspace {
         };
 
         let new_pane = self.add_pane(window, cx);
-        self.move_item(
-            from.clone(),
-            new_pane.clone(),
-            item_id_to_move,
-            0,
-            window,
-            cx,
-        );
+        if let Err(error) = self.move_item(from, new_pane.clone(), item_id_to_move, 0, window, cx) {
+            log::error!("failed to move the item to the new split: {:?}", error);
+            self.force_remove_pane(&new_pane, window, cx);
+            return;
+        }
         self.center
             .split(&pane_to_split, &new_pane, split_direction)
             .unwrap();
         cx.notify();
     }
 
+    /// Move an item of `source` to `destination_index` in `destination`.
+    ///
+    /// The item is removed from `source` before it is added to `destination`, so it is never
+    /// open in both panes. Either pane can belong to the workspace of another window, the move
+    /// fails, leaving the item in `source`, when that window cannot be updated.
     pub fn move_item(
         &mut self,
         source: Entity<Pane>,
@@ -623,7 +959,7 @@ impl Workspace {
         destination_index: usize,
         window: &mut Window,
         cx: &mut Context<Self>,
-    ) {
+    ) -> Result<()> {
         let Some((item_ix, item_handle)) = source
             .read(cx)
             .items()
@@ -631,26 +967,258 @@ impl Workspace {
             .find(|(_, item_handle)| item_handle.item_id() == item_id_to_move)
         else {
             // Tab was closed during drag
-            return;
+            return Ok(());
         };
 
         let item_handle = item_handle.clone();
 
         if source != destination {
             // Close item from previous pane
-            source.update(cx, |source, cx| {
-                source.remove_item(item_ix, false, window, cx);
-            });
+            self.remove_item_from_pane(&source, item_ix, window, cx)?;
         }
 
         // This automatically removes duplicate items in the pane
-        destination.update(cx, |destination, cx| {
-            destination.add_item(item_handle, true, true, Some(destination_index), window, cx);
-            destination.focus(window)
+        let added = self.update_pane(&destination, window, cx, {
+            let item_handle = item_handle.boxed_clone();
+            move |destination, window, cx| {
+                destination.add_item(item_handle, true, true, Some(destination_index), window, cx);
+                destination.focus(window)
+            }
         });
+        if added.is_err() && source != destination {
+            // Put the item back rather than losing it.
+            self.add_item_to_pane(&source, item_handle, item_ix, window, cx)?;
+        }
+        added
+    }
+
+    /// Set how the windows of the items moved to a new window are opened, by
+    /// [`Workspace::move_item_to_new_window`].
+    ///
+    /// The builder opens a window with the given options, like the main window of the app, and
+    /// returns its workspace, so that the new window gets the docks, panels and persistence of
+    /// the app. Without a builder, the new window has a bare workspace which is not persisted.
+    pub fn set_new_window_builder(
+        cx: &mut App,
+        builder: impl Fn(WindowOptions, &mut App) -> Result<Entity<Workspace>> + 'static,
+    ) {
+        cx.set_global(GlobalNewWindowBuilder(Rc::new(builder)));
+    }
+
+    /// Move an item of `source` into the center pane of a new workspace, opened in a new window
+    /// of the same size as this one, by the builder set with
+    /// [`Workspace::set_new_window_builder`].
+    ///
+    /// The new window is placed at `position`, relative to this window, or cascaded from this
+    /// window without a position.
+    ///
+    /// The item is removed from `source` first, and put back if the window cannot be opened.
+    pub fn move_item_to_new_window(
+        &mut self,
+        source: Entity<Pane>,
+        item_id_to_move: EntityId,
+        position: Option<Point<Pixels>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<Entity<Workspace>> {
+        let (item_ix, item_handle) = {
+            let source = source.read(cx);
+            let item_ix = source
+                .index_for_item_id(item_id_to_move)
+                .ok_or_else(|| anyhow!("item not found in the pane"))?;
+            (item_ix, source.items().nth(item_ix).unwrap().boxed_clone())
+        };
+        self.remove_item_from_pane(&source, item_ix, window, cx)?;
+
+        let bounds = window.bounds();
+        let origin = bounds.origin + position.unwrap_or(point(px(32.), px(32.)));
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::new(origin, bounds.size))),
+            display_id: window.display(cx).map(|display| display.id()),
+            ..Default::default()
+        };
+        let new_workspace = self
+            .open_workspace_window(options, cx)
+            .and_then(|new_workspace| {
+                let new_window = new_workspace.read(cx).window_handle;
+                new_window.update(cx, |_, window, cx| {
+                    new_workspace.update(cx, |new_workspace, cx| {
+                        new_workspace.active_center_pane().update(cx, |pane, cx| {
+                            pane.add_item(item_handle.boxed_clone(), true, true, None, window, cx);
+                        });
+                    });
+                })?;
+                Ok(new_workspace)
+            });
+        if new_workspace.is_err() {
+            // Put the item back rather than losing it.
+            self.add_item_to_pane(&source, item_handle, item_ix, window, cx)?;
+        }
+        new_workspace
+    }
+
+    /// Open the window of a new workspace with the builder set with
+    /// [`Workspace::set_new_window_builder`], or with a bare workspace without one.
+    fn open_workspace_window(
+        &self,
+        options: WindowOptions,
+        cx: &mut App,
+    ) -> Result<Entity<Workspace>> {
+        if let Some(builder) = cx.try_global::<GlobalNewWindowBuilder>() {
+            let builder = builder.0.clone();
+            return builder(options, cx);
+        }
+
+        let max_tabs = self.max_tabs;
+        cx.open_window(options, |window, cx| {
+            cx.new(|cx| {
+                let mut workspace = Workspace::new(None, window, cx);
+                workspace.set_max_tabs(max_tabs, window, cx);
+                workspace
+            })
+        })?
+        .root(cx)
+    }
+
+    /// Move a tab dropped at `position`, relative to this window, outside of this window: into
+    /// the pane under it in the window of another workspace, or into a new window when there is
+    /// no workspace there.
+    fn drop_tab_outside_of_window(
+        &mut self,
+        dragged_tab: &DraggedTab,
+        position: Point<Pixels>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        let item_id = dragged_tab.item.item_id();
+        let screen_position = window.bounds().origin + position;
+        let Some((other_window, destination)) =
+            self.pane_in_other_window_at(screen_position, window, cx)
+        else {
+            self.move_item_to_new_window(
+                dragged_tab.pane.clone(),
+                item_id,
+                Some(position),
+                window,
+                cx,
+            )?;
+            return Ok(());
+        };
+
+        let destination_index = destination.read(cx).items_len();
+        self.move_item(
+            dragged_tab.pane.clone(),
+            destination,
+            item_id,
+            destination_index,
+            window,
+            cx,
+        )?;
+        other_window.update(cx, |_, window, _| window.activate_window())
+    }
+
+    /// Returns the window of another workspace at `screen_position`, with the center pane under
+    /// that position, or the active center pane when the position is not over a center pane.
+    fn pane_in_other_window_at(
+        &self,
+        screen_position: Point<Pixels>,
+        window: &Window,
+        cx: &mut App,
+    ) -> Option<(AnyWindowHandle, Entity<Pane>)> {
+        let workspaces = cx.try_global::<WorkspaceRegistry>()?.0.clone();
+        workspaces
+            .iter()
+            .filter(|workspace| workspace.entity_id() != self.weak_self.entity_id())
+            .filter_map(|workspace| workspace.upgrade())
+            .find_map(|workspace| {
+                let other_window = workspace.read(cx).window_handle;
+                if other_window == window.window_handle() {
+                    return None;
+                }
+                let window_bounds = other_window
+                    .update(cx, |_, window, _| window.bounds())
+                    .ok()?;
+                let position = screen_position - window_bounds.origin;
+                let workspace = workspace.read(cx);
+                if !workspace.bounds.contains(&position) {
+                    return None;
+                }
+                let pane = workspace
+                    .center
+                    .pane_at_pixel_position(position)
+                    .cloned()
+                    .unwrap_or_else(|| workspace.active_center_pane());
+                Some((other_window, pane))
+            })
+    }
+
+    /// Remove the item at `item_ix` from `pane`, which can belong to the workspace of another
+    /// window when a tab is dragged between windows.
+    fn remove_item_from_pane(
+        &self,
+        pane: &Entity<Pane>,
+        item_ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.update_pane(pane, window, cx, |pane, window, cx| {
+            pane.remove_item(item_ix, false, window, cx)
+        })
+    }
+
+    /// Add `item` at `index` in `pane`, which can belong to the workspace of another window.
+    fn add_item_to_pane(
+        &self,
+        pane: &Entity<Pane>,
+        item: Box<dyn ItemHandle>,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Result<()> {
+        self.update_pane(pane, window, cx, |pane, window, cx| {
+            pane.add_item(item, false, false, Some(index), window, cx)
+        })
+    }
+
+    /// Update `pane` in the window of its workspace, which is only updated separately when it
+    /// is not the window being updated.
+    fn update_pane(
+        &self,
+        pane: &Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut Pane, &mut Window, &mut Context<Pane>),
+    ) -> Result<()> {
+        let other_window = pane
+            .read(cx)
+            .workspace
+            .upgrade()
+            .filter(|workspace| workspace.entity_id() != cx.entity_id())
+            .map(|workspace| workspace.read(cx).window_handle)
+            .filter(|other_window| *other_window != window.window_handle());
+
+        match other_window {
+            Some(other_window) => other_window.update(cx, |_, window, cx| {
+                pane.update(cx, |pane, cx| update(pane, window, cx))
+            }),
+            None => {
+                pane.update(cx, |pane, cx| update(pane, window, cx));
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the window the workspace was created in.
+    pub fn window_handle(&self) -> AnyWindowHandle {
+        self.window_handle
     }
 
-    fn remove_pane(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut Context<Self>) {
+    pub(crate) fn remove_pane(
+        &mut self,
+        pane: &Entity<Pane>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
         if self.center.remove(pane).unwrap() {
             self.force_remove_pane(pane, window, cx);
 
@@ -664,13 +1232,14 @@ impl Workspace {
         cx.emit(Event::PaneRemoved);
     }
 
-    fn force_remove_pane(
+    pub(crate) fn force_remove_pane(
         &mut self,
         pane: &Entity<Pane>,
         window: &mut Window,
         cx: &mut Context<Workspace>,
     ) {
         self.panes.retain(|p| p != pane);
+        self.pane_subscriptions.remove(&pane.entity_id());
         self.panes
             .last()
             .unwrap()
@@ -685,80 +1254,392 @@ impl Workspace {
         &self.panes
     }
 
+    /// Returns the pane focused last, which is the pane of a dock panel after navigating to it.
     pub fn active_pane(&self) -> &Entity<Pane> {
         &self.active_pane
     }
 
-    // pub fn reopen_closed_item(&mut self, window: &mut Window, cx: &mut Context<Workspace>) -> Task<Result<()>> {
-    //     self.navigate_history(
-    //         self.active_pane().downgrade(),
-    //         NavigationMode::ReopeningClosedItem,
-    //         cx,
-    //     )
-    // }
+    /// Go back to the most recent entry of the navigation history of every pane,
+    /// including the panes of the dock panels.
+    pub fn go_back(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.navigate_timeline(NavigationMode::GoingBack, window, cx);
+    }
 
-    fn activate_pane_at_index(
+    /// Go forward to the entry left most recently by going back, in any pane.
+    pub fn go_forward(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.navigate_timeline(NavigationMode::GoingForward, window, cx);
+    }
+
+    pub fn can_go_back(&self, cx: &App) -> bool {
+        self.history_panes(cx)
+            .iter()
+            .any(|pane| pane.read(cx).can_navigate_backward())
+    }
+
+    pub fn can_go_forward(&self, cx: &App) -> bool {
+        self.history_panes(cx)
+            .iter()
+            .any(|pane| pane.read(cx).can_navigate_forward())
+    }
+
+    /// Returns the center panes in layout order followed by the panes of the dock panels,
+    /// with a label telling where each pane is.
+    pub(crate) fn labeled_panes(&self, cx: &App) -> Vec<(Entity<Pane>, SharedString)> {
+        let mut panes = self
+            .center
+            .panes()
+            .into_iter()
+            .enumerate()
+            .map(|(ix, pane)| (pane.clone(), format!("Pane {}", ix + 1).into()))
+            .collect::<Vec<_>>();
+        for (dock, label) in [
+            (&self.left_dock, "Left Dock"),
+            (&self.bottom_dock, "Bottom Dock"),
+            (&self.right_dock, "Right Dock"),
+        ] {
+            panes.extend(
+                dock.read(cx)
+                    .panels()
+                    .filter_map(|panel| panel.pane(cx))
+                    .map(|pane| (pane, SharedString::from(label))),
+            );
+        }
+        panes
+    }
+
+    /// Open the dock and activate the panel holding `pane`, if it is the pane of a panel.
+    pub(crate) fn reveal_pane(
         &mut self,
-        action: &ActivatePane,
+        pane: &Entity<Pane>,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let panes = self.center.panes();
-        if let Some(pane) = panes.get(action.0).map(|p| (*p).clone()) {
-            cx.focus_view(&pane, window);
-        } else {
-            self.split_and_clone(self.active_pane.clone(), SplitDirection::Right, window, cx);
-        }
-    }
+        for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
+            let Some(panel_ix) = dock
+                .read(cx)
+                .panels()
+                .position(|panel| panel.pane(cx).as_ref() == Some(pane))
+            else {
+                continue;
+            };
 
-    pub fn activate_next_pane(&mut self, window: &mut Window, cx: &mut App) {
-        let panes = self.center.panes();
-        if let Some(ix) = panes.iter().position(|pane| **pane == self.active_pane) {
-            let next_ix = (ix + 1) % panes.len();
-            let next_pane = panes[next_ix].clone();
-            window.focus(&next_pane.focus_handle(cx));
+            dock.update(cx, |dock, cx| {
+                dock.activate_panel(panel_ix, window, cx);
+                dock.set_open(true, window, cx);
+            });
+            cx.notify();
+            return;
         }
     }
 
-    pub fn activate_previous_pane(&mut self, window: &mut Window, cx: &mut App) {
-        let panes = self.center.panes();
-        if let Some(ix) = panes.iter().position(|pane| **pane == self.active_pane) {
-            let prev_ix = cmp::min(ix.wrapping_sub(1), panes.len() - 1);
-            let prev_pane = panes[prev_ix].clone();
-            window.focus(&prev_pane.focus_handle(cx));
+    /// Returns the center panes and the panes of the dock panels.
+    fn history_panes(&self, cx: &App) -> Vec<Entity<Pane>> {
+        let mut panes = self.panes.clone();
+        for dock in [&self.left_dock, &self.bottom_dock, &self.right_dock] {
+            panes.extend(dock.read(cx).panels().filter_map(|panel| panel.pane(cx)));
         }
+        panes
     }
 
-    pub fn activate_pane_in_direction(
+    fn navigate_timeline(
         &mut self,
-        direction: SplitDirection,
+        mode: NavigationMode,
         window: &mut Window,
-        cx: &mut App,
+        cx: &mut Context<Self>,
     ) {
-        use ActivateInDirectionTarget as Target;
-        enum Origin {
-            LeftDock,
-            RightDock,
-            BottomDock,
-            Center,
-        }
+        // The entries are timestamped from the same counter, so the latest one across the
+        // panes is the next step of the timeline. Only one entry is taken at a time, as the
+        // next latest entry may be in another pane.
+        loop {
+            let Some(pane) = self
+                .history_panes(cx)
+                .into_iter()
+                .filter_map(|pane| {
+                    let timestamp = pane.read(cx).nav_history().last_timestamp(mode)?;
+                    Some((timestamp, pane))
+                })
+                .max_by_key(|(timestamp, _)| *timestamp)
+                .map(|(_, pane)| pane)
+            else {
+                break;
+            };
 
-        let origin: Origin = [
-            (&self.left_dock, Origin::LeftDock),
-            (&self.right_dock, Origin::RightDock),
-            (&self.bottom_dock, Origin::BottomDock),
-        ]
-        .into_iter()
-        .find_map(|(dock, origin)| {
-            if dock.focus_handle(cx).contains_focused(window, cx) && dock.read(cx).is_open() {
-                Some(origin)
-            } else {
-                None
+            let Some(entry) = pane.read(cx).nav_history().pop(mode) else {
+                break;
+            };
+            if self.navigate_to_entry(entry, mode, window, cx) {
+                break;
             }
-        })
-        .unwrap_or(Origin::Center);
+        }
+        cx.notify();
+    }
 
-        let get_last_active_pane = || {
+    /// Activate the item of a history entry wherever it is now, recording the location being
+    /// left in the history of the active pane to come back to it.
+    ///
+    /// Returns false when the entry can't be navigated to, such as when its item was closed.
+    fn navigate_to_entry(
+        &mut self,
+        entry: NavigationEntry,
+        mode: NavigationMode,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(item) = entry.item.upgrade() else {
+            return false;
+        };
+        let history_panes = self.history_panes(cx);
+        // Panes of dock panels aren't tracked by the workspace.
+        let Some(item_pane) = self
+            .panes_by_item
+            .get(&item.item_id())
+            .and_then(|pane| pane.upgrade())
+            .or_else(|| {
+                history_panes
+                    .into_iter()
+                    .find(|pane| pane.read(cx).index_for_item_id(item.item_id()).is_some())
+            })
+        else {
+            return false;
+        };
+        let Some(index) = item_pane.read(cx).index_for_item_id(item.item_id()) else {
+            return false;
+        };
+
+        let same_pane = item_pane == self.active_pane;
+        if same_pane && entry.data.is_none() && index == item_pane.read(cx).active_item_index() {
+            return false;
+        }
+
+        if !same_pane {
+            // Leaving the active pane, so record where it was to be able to come back. The focus
+            // moves to the pane of the entry, which must not be recorded as a new step of the
+            // timeline.
+            self.switch_active_pane(&item_pane, mode, cx);
+            self.reveal_pane(&item_pane, window, cx);
+        }
+
+        let navigated = item_pane.update(cx, |item_pane, cx| {
+            // Within the active pane, the item being left is recorded when activating the
+            // entry's item, like the positions the item records while navigating.
+            let nav_history = item_pane.nav_history().clone();
+            let prev_mode = nav_history.mode();
+            nav_history.set_mode(if same_pane {
+                mode
+            } else {
+                NavigationMode::Disabled
+            });
+            item_pane.activate_item(index, true, true, window, cx);
+            let navigated = match entry.data {
+                Some(data) => item.navigate(data, window, cx),
+                None => true,
+            };
+            nav_history.set_mode(prev_mode);
+            navigated
+        });
+
+        navigated
+    }
+
+    /// Make `pane` the active pane, recording the item of the pane being left in its history
+    /// according to `mode`.
+    fn switch_active_pane(&mut self, pane: &Entity<Pane>, mode: NavigationMode, cx: &App) {
+        let left_pane = mem::replace(&mut self.active_pane, pane.clone());
+        if let Some(active_item) = left_pane.read(cx).active_item() {
+            let nav_history = left_pane.read(cx).nav_history();
+            let prev_mode = nav_history.mode();
+            nav_history.set_mode(mode);
+            nav_history.push(None::<()>, Arc::from(active_item.downgrade_item()));
+            nav_history.set_mode(prev_mode);
+        }
+        // The pane of a dock panel is only active after navigating to it.
+        if self.panes.contains(pane) {
+            self.last_active_center_pane = Some(pane.downgrade());
+        }
+    }
+
+    /// Returns the active pane if it is a center pane, otherwise the center pane that was
+    /// active last.
+    fn active_center_pane(&self) -> Entity<Pane> {
+        if self.panes.contains(&self.active_pane) {
+            return self.active_pane.clone();
+        }
+        self.last_active_center_pane
+            .as_ref()
+            .and_then(|pane| pane.upgrade())
+            .filter(|pane| self.panes.contains(pane))
+            .unwrap_or_else(|| self.center.first_pane())
+    }
+
+    /// Reopen the most recently closed item, at the position it had in its pane, or in the
+    /// active pane if that pane is gone.
+    ///
+    /// The item is re-added if it is still alive, otherwise it is recreated if it is a
+    /// [`SerializableItem`]. Closed items that can be neither are skipped.
+    ///
+    /// [`SerializableItem`]: crate::item::SerializableItem
+    pub fn reopen_closed_item(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        while let Some(closed_item) = self.closed_items.pop_back() {
+            let pane = closed_item
+                .pane
+                .upgrade()
+                .filter(|pane| self.panes.contains(pane))
+                .unwrap_or_else(|| self.active_center_pane());
+            let index = closed_item.index;
+            let is_pinned = closed_item.is_pinned;
+
+            if let Some(item) = closed_item.item.upgrade() {
+                // The item may have been opened again since it was closed.
+                let pane = self
+                    .panes_by_item
+                    .get(&item.item_id())
+                    .and_then(|pane| pane.upgrade())
+                    .unwrap_or(pane);
+                pane.update(cx, |pane, cx| {
+                    Self::add_reopened_item(pane, item, index, is_pinned, window, cx)
+                });
+                return Task::ready(Ok(()));
+            }
+
+            if let Some(serialized_item) = closed_item.serialized_item {
+                let task = item::deserialize_item(
+                    &serialized_item.kind,
+                    self.weak_handle(),
+                    pane.downgrade(),
+                    self.database_id,
+                    serialized_item.item_id,
+                    serialized_item.state,
+                    window,
+                    cx,
+                );
+                return cx.spawn_in(window, |_, mut cx| async move {
+                    let item = task.await?;
+                    pane.update_in(&mut cx, |pane, window, cx| {
+                        Self::add_reopened_item(pane, item, index, is_pinned, window, cx)
+                    })?;
+                    Ok(())
+                });
+            }
+        }
+
+        Task::ready(Ok(()))
+    }
+
+    fn add_reopened_item(
+        pane: &mut Pane,
+        item: Box<dyn ItemHandle>,
+        index: usize,
+        is_pinned: bool,
+        window: &mut Window,
+        cx: &mut Context<Pane>,
+    ) {
+        let item_id = item.item_id();
+        pane.add_item(item, true, true, Some(index), window, cx);
+        if is_pinned {
+            if let Some(ix) = pane.index_for_item_id(item_id) {
+                pane.pin_tab_at(ix, cx);
+            }
+        }
+    }
+
+    fn push_closed_item(
+        &mut self,
+        pane: &Entity<Pane>,
+        item: &dyn ItemHandle,
+        index: usize,
+        is_pinned: bool,
+        window: &Window,
+        cx: &App,
+    ) {
+        let serialized_item = item.to_serializable_item_handle(cx).map(|item| {
+            SerializedItem::new(
+                item.serialized_item_kind(),
+                item.item_id().as_u64(),
+                true,
+                item.serialize(window, cx),
+            )
+        });
+
+        if self.closed_items.len() >= MAX_CLOSED_ITEMS {
+            self.closed_items.pop_front();
+        }
+        self.closed_items.push_back(ClosedItem {
+            item: item.downgrade_item(),
+            serialized_item,
+            pane: pane.downgrade(),
+            index,
+            is_pinned,
+        });
+    }
+
+    fn activate_pane_at_index(
+        &mut self,
+        action: &ActivatePane,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let panes = self.center.panes();
+        if let Some(pane) = panes.get(action.0).map(|p| (*p).clone()) {
+            cx.focus_view(&pane, window);
+        } else {
+            self.split_and_clone(self.active_center_pane(), SplitDirection::Right, window, cx);
+        }
+    }
+
+    pub fn activate_next_pane(&mut self, window: &mut Window, cx: &mut App) {
+        let panes = self.center.panes();
+        let active_pane = self.active_center_pane();
+        if let Some(ix) = panes.iter().position(|pane| **pane == active_pane) {
+            let next_ix = (ix + 1) % panes.len();
+            let next_pane = panes[next_ix].clone();
+            window.focus(&next_pane.focus_handle(cx));
+        }
+    }
+
+    pub fn activate_previous_pane(&mut self, window: &mut Window, cx: &mut App) {
+        let panes = self.center.panes();
+        let active_pane = self.active_center_pane();
+        if let Some(ix) = panes.iter().position(|pane| **pane == active_pane) {
+            let prev_ix = cmp::min(ix.wrapping_sub(1), panes.len() - 1);
+            let prev_pane = panes[prev_ix].clone();
+            window.focus(&prev_pane.focus_handle(cx));
+        }
+    }
+
+    pub fn activate_pane_in_direction(
+        &mut self,
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        use ActivateInDirectionTarget as Target;
+        enum Origin {
+            LeftDock,
+            RightDock,
+            BottomDock,
+            Center,
+        }
+
+        let origin: Origin = [
+            (&self.left_dock, Origin::LeftDock),
+            (&self.right_dock, Origin::RightDock),
+            (&self.bottom_dock, Origin::BottomDock),
+        ]
+        .into_iter()
+        .find_map(|(dock, origin)| {
+            if dock.focus_handle(cx).contains_focused(window, cx) && dock.read(cx).is_open() {
+                Some(origin)
+            } else {
+                None
+            }
+        })
+        .unwrap_or(Origin::Center);
+
+        let get_last_active_pane = || {
             self.last_active_center_pane.as_ref().and_then(|p| {
                 let p = p.upgrade()?;
                 (p.read(cx).items_len() != 0).then_some(p)
@@ -867,6 +1748,99 @@ impl Workspace {
         }
     }
 
+    /// Move the active item within the active pane, to the index returned by
+    /// `destination_index` given the index of the item and the number of items.
+    fn move_active_item(
+        &mut self,
+        destination_index: impl FnOnce(usize, usize) -> usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let pane = self.active_pane.clone();
+        let pane_ref = pane.read(cx);
+        let Some(item) = pane_ref.active_item() else {
+            return;
+        };
+        let destination_index =
+            destination_index(pane_ref.active_item_index(), pane_ref.items_len());
+        self.move_item(
+            pane.clone(),
+            pane,
+            item.item_id(),
+            destination_index,
+            window,
+            cx,
+        )
+        .log_err();
+    }
+
+    /// Move the active item to the center pane at `index`, splitting the active pane to the
+    /// right if there is no pane at that index.
+    pub fn move_item_to_pane_at_index(
+        &mut self,
+        index: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let destination = self.center.panes().get(index).map(|pane| (*pane).clone());
+        self.move_active_item_to_pane(destination, SplitDirection::Right, window, cx);
+    }
+
+    /// Move the active item to the pane in `direction`, splitting the active pane in that
+    /// direction if there is no pane there.
+    pub fn move_item_to_pane_in_direction(
+        &mut self,
+        direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let destination = self.find_pane_in_direction(direction, window, cx);
+        self.move_active_item_to_pane(destination, direction, window, cx);
+    }
+
+    fn move_active_item_to_pane(
+        &mut self,
+        destination: Option<Entity<Pane>>,
+        split_direction: SplitDirection,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let source = self.active_pane.clone();
+        // Only the center panes can be split.
+        if !self.panes.contains(&source) {
+            return;
+        }
+        let Some(item) = source.read(cx).active_item() else {
+            return;
+        };
+
+        let destination = match destination {
+            Some(destination) if destination == source => return,
+            Some(destination) => destination,
+            // Moving the only item of a pane to a new split would give the same layout.
+            None if source.read(cx).items_len() == 1 => return,
+            None => self.split_pane(source.clone(), split_direction, window, cx),
+        };
+
+        let destination_index = {
+            let destination = destination.read(cx);
+            if destination.items_len() == 0 {
+                0
+            } else {
+                destination.active_item_index() + 1
+            }
+        };
+        self.move_item(
+            source,
+            destination,
+            item.item_id(),
+            destination_index,
+            window,
+            cx,
+        )
+        .log_err();
+    }
+
     fn handle_pane_focused(
         &mut self,
         pane: Entity<Pane>,
@@ -874,8 +1848,8 @@ impl Workspace {
         cx: &mut Context<Self>,
     ) {
         if self.active_pane != pane {
-            self.active_pane = pane.clone();
-            self.last_active_center_pane = Some(pane.downgrade());
+            // Record the pane being left, for `go_back` to return to it.
+            self.switch_active_pane(&pane, NavigationMode::Normal, cx);
         }
 
         self.dismiss_zoomed_items_to_reveal(None, window, cx);
@@ -921,6 +1895,15 @@ impl Workspace {
                     }
                 }
             }
+            pane::Event::ItemClosed {
+                item,
+                index,
+                is_pinned,
+            } => {
+                self.push_closed_item(pane, item.as_ref(), *index, *is_pinned, window, cx);
+            }
+            // Only the serialized pinned count changes.
+            pane::Event::ItemPinned { .. } | pane::Event::ItemUnpinned { .. } => {}
             pane::Event::Focus => {
                 self.handle_pane_focused(pane.clone(), window, cx);
             }
@@ -987,7 +1970,8 @@ impl Workspace {
         }
 
         if focus_center {
-            self.active_pane.update(cx, |pane, _| pane.focus(window))
+            self.active_center_pane()
+                .update(cx, |pane, _| pane.focus(window))
         }
 
         cx.notify();
@@ -1038,7 +2022,8 @@ impl Workspace {
         }
 
         if focus_center {
-            self.active_pane.update(cx, |pane, _| pane.focus(window))
+            self.active_center_pane()
+                .update(cx, |pane, _| pane.focus(window))
         }
 
         if self.zoomed_position != dock_to_reveal {
@@ -1050,18 +2035,639 @@ impl Workspace {
         cx.notify();
     }
 
-    pub(crate) fn serialize_workspace(&mut self, _window: &mut Window, _cx: &mut Context<Self>) {
-        // if self._schedule_serialize.is_none() {
-        //     self._schedule_serialize = Some(cx.spawn(|this, mut cx| async move {
-        //         cx.background_executor()
-        //             .timer(Duration::from_millis(100))
-        //             .await;
-        //         this.update(&mut cx, |this, cx| {
-        //             this.serialize_workspace_internal(cx).detach();
-        //             this._schedule_serialize.take();
-        //         })
-        //         .log_err();
-        //     }));
-        // }
+    /// Schedule a serialization of the workspace layout, debounced by 100ms.
+    pub(crate) fn serialize_workspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.serialize_task_queued.is_none() {
+            self.serialize_task_queued = Some(cx.spawn_in(window, |this, mut cx| async move {
+                cx.background_executor()
+                    .timer(Duration::from_millis(100))
+                    .await;
+                this.update_in(&mut cx, |this, window, cx| {
+                    this.serialize_workspace_internal(window, cx);
+                    this.serialize_task_queued.take();
+                })
+                .log_err();
+            }));
+        }
+    }
+
+    fn serialize_workspace_internal(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let serialized_workspace = self.serialized_workspace(window, cx);
+
+        if let Some((database_id, persistence)) = self.database_id.zip(persistence::persistence(cx))
+        {
+            let serialized_workspace = serialized_workspace.clone();
+            cx.background_executor()
+                .spawn(
+                    async move { persistence.save_workspace(database_id, &serialized_workspace) },
+                )
+                .detach_and_log_err(cx);
+        }
+
+        cx.emit(Event::WorkspaceSerialized(Box::new(serialized_workspace)));
+    }
+
+    /// Restore the layout saved for this workspace's id in the [`WorkspacePersistence`] backend.
+    ///
+    /// Does nothing if the workspace has no id, no backend is set or nothing was saved yet.
+    ///
+    /// [`WorkspacePersistence`]: crate::persistence::WorkspacePersistence
+    pub fn restore(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Task<Result<()>> {
+        let Some((database_id, persistence)) = self.database_id.zip(persistence::persistence(cx))
+        else {
+            return Task::ready(Ok(()));
+        };
+
+        cx.spawn_in(window, |this, mut cx| async move {
+            let serialized_workspace = cx
+                .background_executor()
+                .spawn(async move { persistence.load_workspace(database_id) })
+                .await?;
+
+            if let Some(serialized_workspace) = serialized_workspace {
+                this.update_in(&mut cx, |this, window, cx| {
+                    this.load(serialized_workspace, window, cx)
+                })?
+                .await?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Restore the layout of the center panes and the docks from a snapshot.
+    ///
+    /// The items of every pane are recreated from the registered [`SerializableItem`]s,
+    /// items of an unknown kind are skipped and panes whose items all failed to restore
+    /// are dropped. If no pane could be restored, the current center panes are kept.
+    ///
+    /// [`SerializableItem`]: crate::item::SerializableItem
+    pub fn load(
+        &mut self,
+        serialized_workspace: SerializedWorkspace,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let restore_item = self.registry_restore_item();
+        self.load_with(serialized_workspace, restore_item, window, cx)
+    }
+
+    /// Returns a [`RestoreItem`] recreating items from the registered [`SerializableItem`]s.
+    ///
+    /// [`SerializableItem`]: crate::item::SerializableItem
+    fn registry_restore_item(&self) -> RestoreItem {
+        let workspace = self.weak_handle();
+        let workspace_id = self.database_id;
+        Rc::new(move |serialized_item, pane, window, cx| {
+            item::deserialize_item(
+                &serialized_item.kind,
+                workspace.clone(),
+                pane,
+                workspace_id,
+                serialized_item.item_id,
+                serialized_item.state.clone(),
+                window,
+                cx,
+            )
+        })
+    }
+
+    pub(crate) fn load_with(
+        &mut self,
+        serialized_workspace: SerializedWorkspace,
+        restore_item: RestoreItem,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        cx.spawn_in(window, |workspace, mut cx| async move {
+            let center_group = serialized_workspace
+                .center_group
+                .deserialize(workspace.clone(), restore_item, &mut cx)
+                .await;
+
+            workspace.update_in(&mut cx, |workspace, window, cx| {
+                if let Some((center_group, active_pane)) = center_group {
+                    workspace.set_center(center_group, active_pane, window, cx);
+                } else {
+                    let pane = workspace.center.first_pane();
+                    workspace.set_active_pane(&pane, window, cx);
+                }
+
+                workspace.load_docks(&serialized_workspace.docks, window, cx);
+                workspace.serialize_workspace(window, cx);
+                cx.notify();
+            })?;
+
+            Ok(())
+        })
+    }
+
+    /// Replace the center pane group, dropping the panes that are no longer part of it.
+    pub(crate) fn set_center(
+        &mut self,
+        root: Member,
+        active_pane: Option<Entity<Pane>>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let old_panes = self.center.panes().into_iter().cloned().collect::<Vec<_>>();
+        self.center = PaneGroup::with_root(root);
+
+        let new_panes = self.center.panes();
+        for pane in old_panes {
+            if new_panes.contains(&&pane) {
+                continue;
+            }
+
+            self.force_remove_pane(&pane, window, cx);
+            self.panes_by_item
+                .retain(|_, p| p.entity_id() != pane.entity_id());
+            cx.emit(Event::PaneRemoved);
+        }
+
+        let active_pane = active_pane.unwrap_or_else(|| self.center.first_pane());
+        self.set_active_pane(&active_pane, window, cx);
+        cx.notify();
+    }
+
+    fn set_active_pane(&mut self, pane: &Entity<Pane>, window: &mut Window, cx: &mut App) {
+        self.active_pane = pane.clone();
+        self.last_active_center_pane = Some(pane.downgrade());
+        pane.update(cx, |pane, _| pane.focus(window));
+    }
+
+    fn load_docks(&mut self, docks: &DockStructure, window: &mut Window, cx: &mut Context<Self>) {
+        self.zoomed = None;
+        self.zoomed_position = None;
+        for (dock, data) in [
+            (self.left_dock.clone(), &docks.left),
+            (self.bottom_dock.clone(), &docks.bottom),
+            (self.right_dock.clone(), &docks.right),
+        ] {
+            dock.update(cx, |dock, cx| dock.load(data, window, cx));
+
+            let dock = dock.read(cx);
+            if data.visible && data.zoom {
+                if let Some(panel) = dock.active_panel() {
+                    self.zoomed = Some(panel.to_any().downgrade());
+                    self.zoomed_position = Some(dock.position());
+                }
+            }
+        }
+        cx.emit(Event::ZoomChanged);
+    }
+
+    /// Returns a snapshot of the current layout of the center panes and the docks.
+    pub fn serialized_workspace(&self, window: &Window, cx: &App) -> SerializedWorkspace {
+        self.snapshot(false, window, cx)
+    }
+
+    /// Returns a snapshot of the layout, including the items that aren't [`SerializableItem`]s
+    /// when `include_all_items` is set, those items have an empty kind and can only be reused
+    /// while they are open.
+    ///
+    /// [`SerializableItem`]: crate::item::SerializableItem
+    fn snapshot(&self, include_all_items: bool, window: &Window, cx: &App) -> SerializedWorkspace {
+        fn serialize_pane_handle(
+            pane: &Entity<Pane>,
+            active: bool,
+            include_all_items: bool,
+            window: &Window,
+            cx: &App,
+        ) -> SerializedPane {
+            let pane = pane.read(cx);
+            let active_item_id = pane.active_item().map(|item| item.item_id());
+            let mut pinned_count = 0;
+            let children = pane
+                .items()
+                .enumerate()
+                .filter_map(|(ix, item)| {
+                    let active = Some(item.item_id()) == active_item_id;
+                    let serialized_item = match item.to_serializable_item_handle(cx) {
+                        Some(item) => SerializedItem::new(
+                            item.serialized_item_kind(),
+                            item.item_id().as_u64(),
+                            active,
+                            item.serialize(window, cx),
+                        ),
+                        None if include_all_items => {
+                            SerializedItem::new("", item.item_id().as_u64(), active, None)
+                        }
+                        None => return None,
+                    };
+                    if pane.is_tab_pinned(ix) {
+                        pinned_count += 1;
+                    }
+                    Some(serialized_item)
+                })
+                .collect();
+
+            SerializedPane {
+                pinned_count,
+                ..SerializedPane::new(children, active)
+            }
+        }
+
+        fn build_serialized_pane_group(
+            member: &Member,
+            active_pane: &Entity<Pane>,
+            include_all_items: bool,
+            window: &Window,
+            cx: &App,
+        ) -> SerializedPaneGroup {
+            match member {
+                Member::Axis(axis) => SerializedPaneGroup::Group {
+                    axis: axis.axis.into(),
+                    flexes: Some(axis.flexes.lock().clone()),
+                    children: axis
+                        .members
+                        .iter()
+                        .map(|member| {
+                            build_serialized_pane_group(
+                                member,
+                                active_pane,
+                                include_all_items,
+                                window,
+                                cx,
+                            )
+                        })
+                        .collect(),
+                },
+                Member::Pane(pane) => SerializedPaneGroup::Pane(serialize_pane_handle(
+                    pane,
+                    pane == active_pane,
+                    include_all_items,
+                    window,
+                    cx,
+                )),
+            }
+        }
+
+        SerializedWorkspace {
+            center_group: build_serialized_pane_group(
+                &self.center.root,
+                &self.active_pane,
+                include_all_items,
+                window,
+                cx,
+            ),
+            docks: DockStructure {
+                left: self.left_dock.read(cx).serialize(window, cx),
+                right: self.right_dock.read(cx).serialize(window, cx),
+                bottom: self.bottom_dock.read(cx).serialize(window, cx),
+            },
+        }
+    }
+
+    /// Save the current arrangement of the center panes, the docks and their items as a
+    /// preset, replacing the preset with the same name if any.
+    pub fn save_layout_preset(
+        &mut self,
+        name: impl Into<SharedString>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let name = name.into();
+        let mut layout = self.snapshot(true, window, cx);
+        // Presets only arrange the panels, they don't reset what the panels show.
+        for dock in [
+            &mut layout.docks.left,
+            &mut layout.docks.right,
+            &mut layout.docks.bottom,
+        ] {
+            for panel in dock.panels.values_mut() {
+                panel.state = None;
+            }
+        }
+
+        let ix = match self.layout_presets.iter().position(|p| p.name == name) {
+            Some(ix) => {
+                self.layout_presets[ix].layout = layout;
+                ix
+            }
+            None => {
+                self.layout_presets.push(LayoutPreset { name, layout });
+                self.layout_presets.len() - 1
+            }
+        };
+        self.active_layout_preset = Some(ix);
+    }
+
+    /// Remove the preset with the given name, returns whether it existed.
+    pub fn remove_layout_preset(&mut self, name: &str) -> bool {
+        let Some(ix) = self.layout_presets.iter().position(|p| p.name == name) else {
+            return false;
+        };
+
+        self.layout_presets.remove(ix);
+        self.active_layout_preset = match self.active_layout_preset {
+            Some(active) if active == ix => None,
+            Some(active) if active > ix => Some(active - 1),
+            active => active,
+        };
+        true
+    }
+
+    /// Returns the names of the layout presets, in the order they were saved.
+    pub fn layout_presets(&self) -> impl Iterator<Item = &SharedString> {
+        self.layout_presets.iter().map(|preset| &preset.name)
+    }
+
+    /// Returns the name of the preset that was last saved or applied.
+    pub fn active_layout_preset(&self) -> Option<&SharedString> {
+        self.active_layout_preset
+            .and_then(|ix| self.layout_presets.get(ix))
+            .map(|preset| &preset.name)
+    }
+
+    /// Re-arrange the workspace as saved in the preset with the given name.
+    ///
+    /// Items that are still open are moved into place rather than recreated, closed items
+    /// are restored if they are [`SerializableItem`]s, and open items that aren't part of
+    /// the preset are kept in the active pane.
+    ///
+    /// [`SerializableItem`]: crate::item::SerializableItem
+    pub fn apply_layout_preset(
+        &mut self,
+        name: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let Some(ix) = self.layout_presets.iter().position(|p| p.name == name) else {
+            return Task::ready(Err(anyhow!("no layout preset named {:?}", name)));
+        };
+
+        self.apply_layout_preset_at(ix, window, cx)
+    }
+
+    fn apply_layout_preset_at(
+        &mut self,
+        ix: usize,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Task<Result<()>> {
+        let mut layout = self.layout_presets[ix].layout.clone();
+        self.active_layout_preset = Some(ix);
+
+        let mut open_items = Vec::new();
+        for pane in self.center.panes() {
+            for item in pane.read(cx).items() {
+                open_items.push((item.item_id().as_u64(), item.boxed_clone()));
+            }
+        }
+
+        let mut preset_panes = layout.center_group.panes_mut();
+        let preset_item_ids = preset_panes
+            .iter()
+            .flat_map(|pane| pane.children.iter().map(|item| item.item_id))
+            .collect::<Vec<_>>();
+        let active_pane_ix = preset_panes
+            .iter()
+            .position(|pane| pane.active)
+            .unwrap_or(0);
+        if let Some(active_pane) = preset_panes.get_mut(active_pane_ix) {
+            for (item_id, _) in &open_items {
+                if !preset_item_ids.contains(item_id) {
+                    active_pane
+                        .children
+                        .push(SerializedItem::new("", *item_id, false, None));
+                }
+            }
+        }
+
+        let open_items = open_items.into_iter().collect::<HashMap<ItemId, _>>();
+        let old_panes = self.center.panes().into_iter().cloned().collect::<Vec<_>>();
+        let registry_restore_item = self.registry_restore_item();
+        let restore_item: RestoreItem = Rc::new(move |serialized_item, pane, window, cx| {
+            match open_items.get(&serialized_item.item_id) {
+                Some(item) => {
+                    // The item is added to the new pane when restored, it must leave the old
+                    // pane first to never be in two panes.
+                    Self::detach_item_from_panes(&old_panes, item.item_id(), window, cx);
+                    Task::ready(Ok(item.boxed_clone()))
+                }
+                None => registry_restore_item(serialized_item, pane, window, cx),
+            }
+        });
+        self.load_with(layout, restore_item, window, cx)
+    }
+
+    /// Detach the item from whichever of `panes` holds it, keeping the emptied panes.
+    fn detach_item_from_panes(
+        panes: &[Entity<Pane>],
+        item_id: EntityId,
+        window: &mut Window,
+        cx: &mut App,
+    ) {
+        for pane in panes {
+            pane.update(cx, |pane, cx| {
+                if let Some(ix) = pane.index_for_item_id(item_id) {
+                    pane.detach_item(ix, window, cx);
+                }
+            });
+        }
+    }
+
+    /// Apply the layout preset after the active one, wrapping around.
+    pub fn activate_next_layout_preset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let len = self.layout_presets.len();
+        if len == 0 {
+            return;
+        }
+
+        let ix = self.active_layout_preset.map_or(0, |ix| (ix + 1) % len);
+        self.apply_layout_preset_at(ix, window, cx)
+            .detach_and_log_err(cx);
+    }
+
+    /// Apply the layout preset before the active one, wrapping around.
+    pub fn activate_previous_layout_preset(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let len = self.layout_presets.len();
+        if len == 0 {
+            return;
+        }
+
+        let ix = self
+            .active_layout_preset
+            .map_or(len - 1, |ix| (ix + len - 1) % len);
+        self.apply_layout_preset_at(ix, window, cx)
+            .detach_and_log_err(cx);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::{Empty, TestAppContext};
+
+    use super::*;
+    use crate::{
+        dock::{Panel, PanelEvent},
+        item::Item,
+    };
+
+    struct TestItem {
+        focus_handle: FocusHandle,
+    }
+
+    impl TestItem {
+        fn new(cx: &mut Context<Self>) -> Self {
+            Self {
+                focus_handle: cx.focus_handle(),
+            }
+        }
+    }
+
+    impl EventEmitter<()> for TestItem {}
+
+    impl Focusable for TestItem {
+        fn focus_handle(&self, _: &App) -> FocusHandle {
+            self.focus_handle.clone()
+        }
+    }
+
+    impl Render for TestItem {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            Empty
+        }
+    }
+
+    impl Item for TestItem {
+        type Event = ();
+    }
+
+    struct TestPanel {
+        pane: Entity<Pane>,
+    }
+
+    impl EventEmitter<PanelEvent> for TestPanel {}
+
+    impl Focusable for TestPanel {
+        fn focus_handle(&self, cx: &App) -> FocusHandle {
+            self.pane.focus_handle(cx)
+        }
+    }
+
+    impl Render for TestPanel {
+        fn render(&mut self, _: &mut Window, _: &mut Context<Self>) -> impl IntoElement {
+            self.pane.clone()
+        }
+    }
+
+    impl Panel for TestPanel {
+        fn persistent_name() -> &'static str {
+            "TestPanel"
+        }
+
+        fn position(&self, _: &Window, _: &App) -> DockPosition {
+            DockPosition::Left
+        }
+
+        fn size(&self, _: &Window, _: &App) -> Pixels {
+            px(200.)
+        }
+
+        fn pane(&self) -> Option<Entity<Pane>> {
+            Some(self.pane.clone())
+        }
+    }
+
+    #[gpui::test]
+    fn test_go_back_into_dock_pane_and_forward(cx: &mut TestAppContext) {
+        cx.update(ui::init);
+        let (workspace, cx) = cx.add_window_view(|window, cx| Workspace::new(None, window, cx));
+
+        let (center_pane, dock_pane) = workspace.update_in(cx, |workspace, window, cx| {
+            let center_pane = workspace.active_pane().clone();
+            let dock_pane = workspace.create_pane_for_panel(window, cx);
+            let panel = cx.new(|_| TestPanel {
+                pane: dock_pane.clone(),
+            });
+            workspace.add_panel(panel, window, cx);
+            for pane in [&center_pane, &dock_pane] {
+                let item: Box<dyn ItemHandle> = Box::new(cx.new(TestItem::new));
+                pane.update(cx, |pane, cx| {
+                    pane.add_item(item, false, false, None, window, cx)
+                });
+            }
+            (center_pane, dock_pane)
+        });
+        cx.run_until_parked();
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            // Move from the center pane to the dock pane and back.
+            workspace.handle_pane_focused(dock_pane.clone(), window, cx);
+            workspace.handle_pane_focused(center_pane.clone(), window, cx);
+
+            workspace.go_back(window, cx);
+            assert!(*workspace.active_pane() == dock_pane);
+            assert!(workspace.can_go_forward(cx));
+
+            workspace.go_forward(window, cx);
+            assert!(*workspace.active_pane() == center_pane);
+            assert!(!workspace.can_go_forward(cx));
+            // The dock pane was recorded when leaving it again.
+            assert!(dock_pane.read(cx).can_navigate_backward());
+        });
+    }
+
+    #[gpui::test]
+    fn test_move_item_from_workspace_in_same_window(cx: &mut TestAppContext) {
+        cx.update(ui::init);
+        let (workspace, cx) = cx.add_window_view(|window, cx| Workspace::new(None, window, cx));
+        let other_workspace = cx.update(|window, cx| cx.new(|cx| Workspace::new(None, window, cx)));
+
+        let (source, item_id) = other_workspace.update_in(cx, |other_workspace, window, cx| {
+            let item: Box<dyn ItemHandle> = Box::new(cx.new(TestItem::new));
+            let item_id = item.item_id();
+            let source = other_workspace.active_pane().clone();
+            source.update(cx, |pane, cx| {
+                pane.add_item(item, false, false, None, window, cx)
+            });
+            (source, item_id)
+        });
+
+        workspace.update_in(cx, |workspace, window, cx| {
+            let destination = workspace.active_pane().clone();
+            workspace
+                .move_item(source.clone(), destination.clone(), item_id, 0, window, cx)
+                .unwrap();
+
+            // The item is only open in the destination pane.
+            assert_eq!(source.read(cx).items_len(), 0);
+            assert_eq!(destination.read(cx).items_len(), 1);
+        });
+    }
+
+    #[gpui::test]
+    fn test_move_item_to_new_window_with_builder(cx: &mut TestAppContext) {
+        cx.update(|cx| {
+            ui::init(cx);
+            Workspace::set_new_window_builder(cx, |options, cx| {
+                cx.open_window(options, |window, cx| {
+                    cx.new(|cx| Workspace::new(Some(WorkspaceId::from(1)), window, cx))
+                })?
+                .root(cx)
+            });
+        });
+        let (workspace, cx) = cx.add_window_view(|window, cx| Workspace::new(None, window, cx));
+
+        let new_workspace = workspace.update_in(cx, |workspace, window, cx| {
+            let item: Box<dyn ItemHandle> = Box::new(cx.new(TestItem::new));
+            let item_id = item.item_id();
+            let source = workspace.active_pane().clone();
+            source.update(cx, |pane, cx| {
+                pane.add_item(item, false, false, None, window, cx)
+            });
+
+            let new_workspace = workspace
+                .move_item_to_new_window(source.clone(), item_id, None, window, cx)
+                .unwrap();
+            assert_eq!(source.read(cx).items_len(), 0);
+            new_workspace
+        });
+
+        new_workspace.read_with(cx, |new_workspace, cx| {
+            assert_eq!(new_workspace.database_id(), Some(WorkspaceId::from(1)));
+            assert_eq!(new_workspace.active_pane().read(cx).items_len(), 1);
+        });
     }
 }